@@ -0,0 +1,155 @@
+//! Micro-benchmarks of the `Schema` mutations executed by transfers and approvals,
+//! over wallets with a growing amount of approved transactions.
+
+use criterion::{criterion_group, criterion_main, Bencher, Criterion};
+use exonum::blockchain::{config::ValidatorKeys, Blockchain, GenesisConfig};
+use exonum::crypto::{self, Hash, PublicKey};
+use exonum::node::ApiSender;
+use exonum::storage::{Fork, MemoryDB};
+use exonum_multisig::asset::NATIVE_ASSET_ID;
+use exonum_multisig::config::ServiceConfig;
+use exonum_multisig::schema::Schema;
+use exonum_multisig::service::Service;
+use exonum_multisig::transaction::{ApprovedTransaction, PendingKind, PendingTransaction};
+use exonum_multisig::wallet::{Signer, SpendingLimit, Wallet};
+use futures::sync::mpsc;
+
+/// Amounts of approved transactions in the benchmarked wallet.
+const TXS_LENGTHS: [usize; 4] = [0, 100, 1_000, 10_000];
+
+fn blockchain() -> Blockchain {
+    let (consensus_key, _) = crypto::gen_keypair();
+    let (service_key, service_secret_key) = crypto::gen_keypair();
+
+    let (api_sender, _) = mpsc::channel(1);
+    let mut blockchain = Blockchain::new(
+        MemoryDB::new(),
+        vec![Box::new(Service::new(ServiceConfig::new(&service_key)))],
+        service_key,
+        service_secret_key,
+        ApiSender::new(api_sender),
+    );
+    let genesis = GenesisConfig::new(
+        vec![ValidatorKeys {
+            consensus_key,
+            service_key,
+        }]
+        .into_iter(),
+    );
+    blockchain.initialize(genesis).unwrap();
+    blockchain
+}
+
+fn pending_tx(recipient: &PublicKey, seed: u64) -> PendingTransaction {
+    let tx_hash = crypto::hash(&seed.to_le_bytes());
+    PendingTransaction::new(
+        &tx_hash,
+        PendingKind::Transfer,
+        recipient,
+        NATIVE_ASSET_ID,
+        1,
+    )
+}
+
+/// Returns a wallet with a signer, `txs_len` approved transactions and one pending transaction.
+fn wallet(txs_len: usize) -> (Wallet, PendingTransaction) {
+    let (pub_key, _) = crypto::gen_keypair();
+    let (signer, _) = crypto::gen_keypair();
+    let (recipient, _) = crypto::gen_keypair();
+
+    let txs = (0..txs_len as u64)
+        .map(|seed| {
+            let tx = pending_tx(&recipient, seed);
+            ApprovedTransaction {
+                tx_hash: tx.tx_hash,
+                kind: tx.kind,
+                recipient: tx.recipient,
+                asset_id: tx.asset_id,
+                amount: tx.amount,
+                period: tx.period,
+                payments: tx.payments,
+                approvals: vec![signer],
+                confirmation_block: seed,
+            }
+        })
+        .collect();
+    let pending_tx =
+        pending_tx(&recipient, txs_len as u64).with_signers(&[Signer::new(&signer, 1)], 2);
+
+    let wallet = Wallet::new(
+        &pub_key,
+        "Bench",
+        u64::from(u32::max_value()),
+        vec![Signer::new(&signer, 1)],
+        vec![],
+        vec![],
+        vec![pending_tx.clone()],
+        txs,
+        vec![],
+        SpendingLimit::default(),
+        vec![],
+        0,
+        &Hash::zero(),
+    );
+    (wallet, pending_tx)
+}
+
+fn bench_over_txs<F>(c: &mut Criterion, id: &str, mut routine: F)
+where
+    F: FnMut(&mut Schema<&mut Fork>, &Wallet, &PendingTransaction) + 'static,
+{
+    let blockchain = blockchain();
+    c.bench_function_over_inputs(
+        id,
+        move |b: &mut Bencher, &txs_len: &usize| {
+            let (wallet, pending_tx) = wallet(txs_len);
+            b.iter_with_setup(
+                || blockchain.fork(),
+                |mut fork| routine(&mut Schema::new(&mut fork), &wallet, &pending_tx),
+            );
+        },
+        TXS_LENGTHS.iter().cloned(),
+    );
+}
+
+fn bench_schema(c: &mut Criterion) {
+    let transaction = crypto::hash(b"transaction");
+
+    bench_over_txs(c, "add_pending_tx", move |schema, wallet, pending_tx| {
+        let mut pending_tx = pending_tx.clone();
+        pending_tx.tx_hash = transaction;
+        schema.add_pending_tx(wallet, pending_tx, &transaction);
+    });
+    bench_over_txs(c, "sign_pending_tx", move |schema, wallet, pending_tx| {
+        schema
+            .sign_pending_tx(
+                wallet,
+                &pending_tx.tx_hash,
+                &wallet.signers[0].pub_key,
+                &transaction,
+            )
+            .unwrap();
+    });
+    bench_over_txs(
+        c,
+        "confirm_pending_tx",
+        move |schema, wallet, pending_tx| {
+            schema
+                .confirm_pending_tx(wallet, pending_tx, 1, &transaction)
+                .unwrap();
+        },
+    );
+    bench_over_txs(c, "decrease_wallet_balance", move |schema, wallet, _| {
+        schema
+            .decrease_wallet_balance(wallet, NATIVE_ASSET_ID, 1, &transaction)
+            .unwrap();
+    });
+    bench_over_txs(c, "increase_wallet_balance", move |schema, wallet, _| {
+        schema
+            .increase_wallet_balance(wallet, NATIVE_ASSET_ID, 1, &transaction)
+            .unwrap();
+    });
+}
+
+criterion_group!(benches, bench_schema);
+criterion_main!(benches);
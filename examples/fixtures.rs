@@ -0,0 +1,243 @@
+//! Generates `example-data`: signed transactions of the multisig transfer scenario
+//! as markdown, and as `scenario.json` for tools and tests.
+//!
+//! Keys are derived from the wallet names, so the output is the same on every run:
+//! `cargo run --example fixtures -- [output directory]`.
+
+use exonum::crypto::{self, Hash, PublicKey, SecretKey, Seed};
+use exonum::messages::{Message, RawTransaction, Signed};
+use exonum_multisig::asset::NATIVE_ASSET_ID;
+use exonum_multisig::service::SERVICE_ID;
+use exonum_multisig::transaction::{
+    AcceptSignerInviteTx, AddSignerTx, CreateWalletTx, SignTx, TransferTx, WalletTransaction,
+};
+use serde_derive::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+const INITIAL_BALANCE: u64 = 100;
+const TRANSFER_AMOUNT: u64 = 50;
+
+struct Person {
+    name: &'static str,
+    pub_key: PublicKey,
+    secret_key: SecretKey,
+}
+
+impl Person {
+    fn new(name: &'static str) -> Self {
+        let seed = crypto::hash(name.as_bytes());
+        let (pub_key, secret_key) =
+            crypto::gen_keypair_from_seed(&Seed::from_slice(seed.as_ref()).unwrap());
+        Self {
+            name,
+            pub_key,
+            secret_key,
+        }
+    }
+
+    fn sign<T: Into<WalletTransaction>>(&self, title: String, tx: T) -> Fixture {
+        let transaction = tx.into();
+        let signed: Signed<RawTransaction> = Message::sign_transaction(
+            transaction.clone(),
+            SERVICE_ID,
+            self.pub_key,
+            &self.secret_key,
+        );
+
+        Fixture {
+            title,
+            author: self.pub_key,
+            tx_hash: signed.hash(),
+            transaction,
+            signed: hex::encode(signed.serialize()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Fixture {
+    title: String,
+    author: PublicKey,
+    transaction: WalletTransaction,
+    tx_hash: Hash,
+    signed: String,
+}
+
+/// Transactions committed in one block.
+#[derive(Serialize)]
+struct Step {
+    name: &'static str,
+    transactions: Vec<Fixture>,
+}
+
+fn main() {
+    let output = std::env::args()
+        .nth(1)
+        .map_or_else(|| PathBuf::from("example-data"), PathBuf::from);
+
+    let alice = Person::new("Alice");
+    let bob = Person::new("Bob");
+    let carl = Person::new("Carl");
+    let dean = Person::new("Dean");
+    let people = [&alice, &bob, &carl, &dean];
+
+    let create_wallets = people
+        .iter()
+        .map(|person| {
+            let title = format!("Create wallet for {}", person.name);
+            person.sign(
+                title,
+                CreateWalletTx {
+                    name: person.name.to_owned(),
+                },
+            )
+        })
+        .collect();
+
+    let add_signers = [&bob, &carl]
+        .iter()
+        .map(|signer| {
+            let title = format!("Add {} as signer to {}", signer.name, alice.name);
+            alice.sign(
+                title,
+                AddSignerTx {
+                    signer: signer.pub_key,
+                    weight: 1,
+                },
+            )
+        })
+        .collect();
+
+    let accept_invites = [&bob, &carl]
+        .iter()
+        .map(|signer| {
+            let title = format!("Accept invite to {} by {}", alice.name, signer.name);
+            signer.sign(
+                title,
+                AcceptSignerInviteTx {
+                    wallet: alice.pub_key,
+                },
+            )
+        })
+        .collect();
+
+    let transfer = alice.sign(
+        format!("Transfer funds from {} to {}", alice.name, dean.name),
+        TransferTx {
+            recipient: dean.pub_key,
+            asset_id: NATIVE_ASSET_ID.to_owned(),
+            amount: TRANSFER_AMOUNT,
+            seed: 0,
+        },
+    );
+
+    let sign_pending = [&bob, &carl]
+        .iter()
+        .map(|signer| {
+            signer.sign(
+                format!("Sign transaction by {}", signer.name),
+                SignTx {
+                    origin: alice.pub_key,
+                    tx_hash: transfer.tx_hash,
+                    seed: 0,
+                },
+            )
+        })
+        .collect();
+
+    let steps = vec![
+        Step {
+            name: "1.create-wallets",
+            transactions: create_wallets,
+        },
+        Step {
+            name: "2.add-signers",
+            transactions: add_signers,
+        },
+        Step {
+            name: "3.accept-invites",
+            transactions: accept_invites,
+        },
+        Step {
+            name: "4.transfer-funds",
+            transactions: vec![transfer],
+        },
+        Step {
+            name: "5.sing-pending",
+            transactions: sign_pending,
+        },
+    ];
+
+    let mut balances: BTreeMap<&str, u64> = people
+        .iter()
+        .map(|person| (person.name, INITIAL_BALANCE))
+        .collect();
+    balances.insert(alice.name, INITIAL_BALANCE - TRANSFER_AMOUNT);
+    balances.insert(dean.name, INITIAL_BALANCE + TRANSFER_AMOUNT);
+
+    fs::create_dir_all(&output).expect("Couldn't create output directory");
+    for step in &steps {
+        let path = output.join(format!("{}.md", step.name));
+        fs::write(&path, step_markdown(step, &people)).expect("Couldn't write markdown");
+    }
+
+    let scenario = json!({
+        "service_config": { "initial_balance": INITIAL_BALANCE },
+        "wallets": people
+            .iter()
+            .map(|person| json!({
+                "name": person.name,
+                "pub_key": person.pub_key,
+                "secret_key": person.secret_key,
+                "balance": balances[person.name],
+            }))
+            .collect::<Vec<_>>(),
+        "steps": steps,
+    });
+    let json = serde_json::to_string_pretty(&scenario).expect("Couldn't serialize scenario");
+    fs::write(output.join("scenario.json"), json + "\n").expect("Couldn't write scenario");
+}
+
+fn step_markdown(step: &Step, people: &[&Person]) -> String {
+    let mut markdown = String::new();
+    for (index, fixture) in step.transactions.iter().enumerate() {
+        if index > 0 {
+            markdown.push('\n');
+        }
+
+        // Payload is the transaction body, without the transaction type
+        let payload = match serde_json::to_value(&fixture.transaction) {
+            Ok(Value::Object(map)) => map.into_iter().next().map(|(_, body)| body),
+            _ => None,
+        }
+        .expect("Couldn't serialize transaction");
+
+        writeln!(markdown, "### {}\n", fixture.title).unwrap();
+        writeln!(markdown, "#### Payload").unwrap();
+        writeln!(
+            markdown,
+            "```\n{}\n```",
+            serde_json::to_string_pretty(&payload).unwrap()
+        )
+        .unwrap();
+        writeln!(markdown, "#### Details").unwrap();
+
+        if let WalletTransaction::CreateWallet(_) = fixture.transaction {
+            let author = people
+                .iter()
+                .find(|person| person.pub_key == fixture.author)
+                .unwrap();
+            writeln!(markdown, "- Public {}", author.pub_key.to_hex()).unwrap();
+            writeln!(markdown, "- Secret {}", author.secret_key.to_hex()).unwrap();
+        } else {
+            writeln!(markdown, "- Author {}", fixture.author.to_hex()).unwrap();
+        }
+        writeln!(markdown, "- Hash {}", fixture.tx_hash.to_hex()).unwrap();
+        writeln!(markdown, "- Signed transaction {}", fixture.signed).unwrap();
+    }
+    markdown
+}
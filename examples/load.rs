@@ -0,0 +1,398 @@
+//! Load generator: creates wallets with signers on a running node and submits rounds
+//! of transfers and approvals, reporting throughput and commit latency of every round.
+//!
+//! Each round submits `--transfers` transfers from every wallet together with approvals
+//! of the previous round transfers by `--approvals` signers, so the wallets `txs` grow
+//! from round to round. Transfer amount is lowered to `--amount` the balance affords after
+//! the fees, start the node with enough `initial_balance` for all transfers and their fees:
+//! `cargo run --release --example load -- --wallets 10 --signers 3 --approvals 2`.
+
+use exonum::crypto::{self, Hash, PublicKey, SecretKey};
+use exonum::messages::{Message, RawTransaction, Signed};
+use exonum_multisig::api::FeesInfo;
+use exonum_multisig::asset::NATIVE_ASSET_ID;
+use exonum_multisig::config::TransactionFees;
+use exonum_multisig::service::SERVICE_ID;
+use exonum_multisig::transaction::{
+    AcceptSignerInviteTx, AddSignerTx, CreateWalletTx, SignTx, TransferTx, WalletTransaction,
+};
+use exonum_multisig::wallet::Wallet;
+use failure::{bail, Error};
+use serde_derive::Deserialize;
+use serde_json::json;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+type KeyPair = (PublicKey, SecretKey);
+
+struct Options {
+    node: String,
+    service_name: String,
+    wallets: usize,
+    signers: usize,
+    approvals: usize,
+    transfers: usize,
+    rounds: usize,
+    amount: u64,
+    timeout: Duration,
+}
+
+impl Options {
+    fn from_args() -> Result<Self, Error> {
+        let mut options = Self {
+            node: "http://127.0.0.1:8000".to_owned(),
+            service_name: "cryptocurrency".to_owned(),
+            wallets: 10,
+            signers: 3,
+            approvals: 3,
+            transfers: 1,
+            rounds: 10,
+            amount: 1,
+            timeout: Duration::from_secs(60),
+        };
+
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        for pair in args.chunks(2) {
+            let value = match pair.get(1) {
+                Some(value) => value,
+                None => bail!("Missing value of `{}`", pair[0]),
+            };
+            match pair[0].as_str() {
+                "--node" => options.node = value.trim_end_matches('/').to_owned(),
+                "--service-name" => options.service_name = value.clone(),
+                "--wallets" => options.wallets = value.parse()?,
+                "--signers" => options.signers = value.parse()?,
+                "--approvals" => options.approvals = value.parse()?,
+                "--transfers" => options.transfers = value.parse()?,
+                "--rounds" => options.rounds = value.parse()?,
+                "--amount" => options.amount = value.parse()?,
+                "--timeout" => options.timeout = Duration::from_secs(value.parse()?),
+                other => bail!("Unknown option `{}`", other),
+            }
+        }
+
+        if options.wallets < 2 {
+            bail!("At least 2 wallets are required to transfer between them");
+        }
+        if options.approvals > options.signers {
+            bail!("Amount of approvals is greater than amount of signers");
+        }
+        Ok(options)
+    }
+}
+
+#[derive(Deserialize)]
+struct TransactionResponse {
+    tx_hash: Hash,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum TransactionInfo {
+    InPool,
+    Committed { status: TransactionStatus },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum TransactionStatus {
+    Success,
+    Error,
+    Panic,
+}
+
+struct Client<'a> {
+    http: reqwest::Client,
+    options: &'a Options,
+}
+
+impl<'a> Client<'a> {
+    fn submit(&self, tx: &Signed<RawTransaction>) -> Result<Hash, Error> {
+        let url = format!("{}/api/explorer/v1/transactions", self.options.node);
+        let response: TransactionResponse = self
+            .http
+            .post(&url)
+            .json(&json!({ "tx_body": hex::encode(tx.serialize()) }))
+            .send()?
+            .error_for_status()?
+            .json()?;
+        Ok(response.tx_hash)
+    }
+
+    /// Returns the transaction status, or `None` if it is not committed yet.
+    fn status(&self, tx_hash: &Hash) -> Result<Option<TransactionStatus>, Error> {
+        let url = format!("{}/api/explorer/v1/transactions", self.options.node);
+        let response = self
+            .http
+            .get(&url)
+            .query(&[("hash", tx_hash.to_hex())])
+            .send()?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        match response.error_for_status()?.json()? {
+            TransactionInfo::InPool => Ok(None),
+            TransactionInfo::Committed { status } => Ok(Some(status)),
+        }
+    }
+
+    fn wallet(&self, pub_key: &PublicKey) -> Result<Wallet, Error> {
+        let url = format!(
+            "{}/api/services/{}/v1/wallet",
+            self.options.node, self.options.service_name
+        );
+        Ok(self
+            .http
+            .get(&url)
+            .query(&[("pub_key", pub_key.to_hex())])
+            .send()?
+            .error_for_status()?
+            .json()?)
+    }
+
+    fn fees(&self) -> Result<TransactionFees, Error> {
+        let url = format!(
+            "{}/api/services/{}/v1/fees",
+            self.options.node, self.options.service_name
+        );
+        let info: FeesInfo = self.http.get(&url).send()?.error_for_status()?.json()?;
+        Ok(info.fees)
+    }
+
+    /// Submits transactions and waits until all of them are committed.
+    fn run_batch(&self, txs: &[Signed<RawTransaction>]) -> Result<BatchReport, Error> {
+        let started = Instant::now();
+        let mut pending = Vec::with_capacity(txs.len());
+        for tx in txs {
+            pending.push((self.submit(tx)?, Instant::now()));
+        }
+
+        let mut report = BatchReport::default();
+        while !pending.is_empty() {
+            if started.elapsed() > self.options.timeout {
+                bail!("{} transactions are not committed in time", pending.len());
+            }
+            thread::sleep(POLL_INTERVAL);
+
+            let mut still_pending = Vec::with_capacity(pending.len());
+            for (tx_hash, submitted) in pending {
+                match self.status(&tx_hash)? {
+                    Some(status) => {
+                        match status {
+                            TransactionStatus::Success => {}
+                            TransactionStatus::Error | TransactionStatus::Panic => {
+                                report.failed += 1
+                            }
+                        }
+                        report.latencies.push(submitted.elapsed());
+                    }
+                    None => still_pending.push((tx_hash, submitted)),
+                }
+            }
+            pending = still_pending;
+        }
+
+        report.elapsed = started.elapsed();
+        report.latencies.sort();
+        Ok(report)
+    }
+}
+
+#[derive(Default)]
+struct BatchReport {
+    failed: usize,
+    elapsed: Duration,
+    latencies: Vec<Duration>,
+}
+
+impl BatchReport {
+    fn throughput(&self) -> f64 {
+        self.latencies.len() as f64 / duration_secs(self.elapsed)
+    }
+
+    fn latency(&self, percentile: usize) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::default();
+        }
+        let index = (self.latencies.len() * percentile / 100).min(self.latencies.len() - 1);
+        self.latencies[index]
+    }
+}
+
+fn duration_secs(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1e9
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration_secs(duration) * 1000.0
+}
+
+fn sign<T: Into<WalletTransaction>>(tx: T, keys: &KeyPair) -> Signed<RawTransaction> {
+    Message::sign_transaction(tx.into(), SERVICE_ID, keys.0, &keys.1)
+}
+
+struct MultisigWallet {
+    owner: KeyPair,
+    signers: Vec<KeyPair>,
+}
+
+fn main() -> Result<(), Error> {
+    let options = Options::from_args()?;
+    let wallets: Vec<MultisigWallet> = (0..options.wallets)
+        .map(|_| MultisigWallet {
+            owner: crypto::gen_keypair(),
+            signers: (0..options.signers)
+                .map(|_| crypto::gen_keypair())
+                .collect(),
+        })
+        .collect();
+    let client = Client {
+        http: reqwest::Client::new(),
+        options: &options,
+    };
+
+    // Signers get wallets too, so they can pay fees of their approvals
+    let create_wallets: Vec<_> = wallets
+        .iter()
+        .flat_map(|wallet| wallet.signers.iter().chain(Some(&wallet.owner)))
+        .enumerate()
+        .map(|(index, keys)| {
+            let name = format!("load-{}", index);
+            sign(CreateWalletTx { name }, keys)
+        })
+        .collect();
+    let report = client.run_batch(&create_wallets)?;
+    if report.failed > 0 {
+        bail!("{} wallets are not created", report.failed);
+    }
+
+    let invite_signers: Vec<_> = wallets
+        .iter()
+        .flat_map(|wallet| {
+            wallet.signers.iter().map(move |signer| {
+                let tx = AddSignerTx {
+                    signer: signer.0,
+                    weight: 1,
+                };
+                sign(tx, &wallet.owner)
+            })
+        })
+        .collect();
+    let report = client.run_batch(&invite_signers)?;
+    if report.failed > 0 {
+        bail!("{} signers are not invited", report.failed);
+    }
+
+    let accept_invites: Vec<_> = wallets
+        .iter()
+        .flat_map(|wallet| {
+            let owner = wallet.owner.0;
+            wallet
+                .signers
+                .iter()
+                .map(move |signer| sign(AcceptSignerInviteTx { wallet: owner }, signer))
+        })
+        .collect();
+    let report = client.run_batch(&accept_invites)?;
+    if report.failed > 0 {
+        bail!("{} signers are not added", report.failed);
+    }
+
+    // Setup fees are paid by now, so the balances are left for transfers and approvals
+    let fees = client.fees()?;
+    let required = options.transfers as u64 * options.rounds as u64;
+    let balance = client.wallet(&wallets[0].owner.0)?.balance;
+    let per_transfer = balance / required.max(1);
+    if per_transfer <= fees.transfer {
+        bail!(
+            "Balance {} is not enough for {} transfers with fee {}, raise node `initial_balance`",
+            balance,
+            required,
+            fees.transfer
+        );
+    }
+    let amount = options.amount.min(per_transfer - fees.transfer);
+
+    if let Some(signer) = wallets[0].signers.first() {
+        let balance = client.wallet(&signer.0)?.balance;
+        if balance < required.saturating_mul(fees.sign) {
+            bail!(
+                "Signer balance {} is not enough for {} approvals with fee {}, raise it",
+                balance,
+                required,
+                fees.sign
+            );
+        }
+    }
+
+    println!(
+        "{} wallets with {} signers, {} transfers of {} per wallet per round, {} approvals each",
+        options.wallets, options.signers, options.transfers, amount, options.approvals
+    );
+    println!("round\ttxs\tfailed\ttx/s\tp50 ms\tp99 ms\tmax ms\tavg wallet txs");
+
+    let mut seed = 0;
+    let mut previous_transfers: Vec<(usize, Hash)> = vec![];
+    for round in 0..=options.rounds {
+        // The last round only approves transfers of the previous one
+        let mut batch = vec![];
+        let mut transfers = vec![];
+        if round < options.rounds {
+            for (index, wallet) in wallets.iter().enumerate() {
+                let recipient = &wallets[(index + 1) % wallets.len()].owner;
+                for _ in 0..options.transfers {
+                    seed += 1;
+                    let tx = sign(
+                        TransferTx {
+                            recipient: recipient.0,
+                            asset_id: NATIVE_ASSET_ID.to_owned(),
+                            amount,
+                            seed,
+                        },
+                        &wallet.owner,
+                    );
+                    transfers.push((index, tx.hash()));
+                    batch.push(tx);
+                }
+            }
+        }
+
+        for (index, tx_hash) in &previous_transfers {
+            let wallet = &wallets[*index];
+            for signer in wallet.signers.iter().take(options.approvals) {
+                batch.push(sign(
+                    SignTx {
+                        origin: wallet.owner.0,
+                        tx_hash: *tx_hash,
+                        seed: 0,
+                    },
+                    signer,
+                ));
+            }
+        }
+
+        let report = client.run_batch(&batch)?;
+        let mut wallet_txs = 0;
+        for wallet in &wallets {
+            wallet_txs += client.wallet(&wallet.owner.0)?.txs.len();
+        }
+
+        println!(
+            "{}\t{}\t{}\t{:.1}\t{:.1}\t{:.1}\t{:.1}\t{:.1}",
+            round,
+            batch.len(),
+            report.failed,
+            report.throughput(),
+            millis(report.latency(50)),
+            millis(report.latency(99)),
+            millis(report.latency(100)),
+            wallet_txs as f64 / wallets.len() as f64
+        );
+        previous_transfers = transfers;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+#![no_main]
+
+use exonum::blockchain::TransactionSet;
+use exonum::messages::RawTransaction;
+use exonum_multisig::service::SERVICE_ID;
+use exonum_multisig::transaction::WalletTransaction;
+use exonum_multisig_fuzz::{service_transaction, sign, SeededChain};
+use libfuzzer_sys::fuzz_target;
+use std::cell::RefCell;
+
+thread_local! {
+    // Seeding takes a few blocks, so the chain is seeded once and rolled back after every input
+    static CHAIN: RefCell<SeededChain> = RefCell::new(SeededChain::new());
+}
+
+// First byte selects the author among the seeded wallets, the validator and the issuer,
+// the rest is the service transaction.
+fuzz_target!(|data: &[u8]| {
+    if data.is_empty() {
+        return;
+    }
+    let transaction = match service_transaction(&data[1..]) {
+        Some(transaction) => transaction,
+        None => return,
+    };
+    let raw = RawTransaction::new(SERVICE_ID, transaction.clone());
+    if WalletTransaction::tx_from_raw(raw).is_err() {
+        return;
+    }
+
+    CHAIN.with(|chain| {
+        let mut chain = chain.borrow_mut();
+        let authors = chain.authors();
+        let author = &authors[data[0] as usize % authors.len()];
+        chain.execute(sign(transaction, author));
+    });
+});
@@ -0,0 +1,20 @@
+#![no_main]
+
+use exonum::blockchain::Service as _;
+use exonum::messages::RawTransaction;
+use exonum_multisig::config::ServiceConfig;
+use exonum_multisig::service::{Service, SERVICE_ID};
+use exonum_multisig_fuzz::{keypair, service_transaction};
+use libfuzzer_sys::fuzz_target;
+
+thread_local! {
+    static SERVICE: Service = Service::new(ServiceConfig::new(&keypair(0).0));
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(transaction) = service_transaction(data) {
+        SERVICE.with(|service| {
+            let _ = service.tx_from_raw(RawTransaction::new(SERVICE_ID, transaction));
+        });
+    }
+});
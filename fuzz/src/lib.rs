@@ -0,0 +1,105 @@
+//! Helpers shared by the fuzz targets.
+
+#[path = "../../tests/common/mod.rs"]
+mod common;
+
+use exonum::blockchain::TransactionErrorType;
+use exonum::crypto::{self, Seed, SEED_LENGTH};
+use exonum::messages::{RawTransaction, ServiceTransaction, Signed};
+
+pub use common::{sign, KeyPair, TestKit};
+
+/// Amount of seeded wallets.
+pub const WALLETS: usize = 4;
+
+pub fn keypair(index: u8) -> KeyPair {
+    crypto::gen_keypair_from_seed(&Seed::new([index; SEED_LENGTH]))
+}
+
+/// Reads the service transaction from fuzzer input, the first two bytes are its id.
+pub fn service_transaction(data: &[u8]) -> Option<ServiceTransaction> {
+    if data.len() < 2 {
+        return None;
+    }
+
+    let transaction_id = u16::from_le_bytes([data[0], data[1]]);
+    Some(ServiceTransaction::from_raw_unchecked(
+        transaction_id,
+        data[2..].to_vec(),
+    ))
+}
+
+/// Blockchain with funded wallets, a multisig wallet and its pending transfer.
+///
+/// Chain is seeded once, every `execute` starts from the seeded state.
+pub struct SeededChain {
+    kit: TestKit,
+    pub wallets: Vec<KeyPair>,
+}
+
+impl Default for SeededChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SeededChain {
+    pub fn new() -> Self {
+        let mut kit = TestKit::with_config(|config| {
+            config.initial_balance = 100;
+            config.fees.transfer = 1;
+        });
+        let wallets: Vec<_> = (0..WALLETS as u8).map(keypair).collect();
+
+        let mut txs: Vec<_> = wallets
+            .iter()
+            .map(|keys| common::create_wallet(keys, "Wallet"))
+            .collect();
+        let (owner, signer, recipient) = (&wallets[0], &wallets[1], &wallets[2]);
+        let (invite_tx, accept_tx) = common::add_signer(owner, signer);
+        txs.push(invite_tx);
+        txs.push(accept_tx);
+        txs.push(common::transfer(owner, recipient, 10));
+        kit.create_block(txs);
+        kit.checkpoint();
+
+        Self { kit, wallets }
+    }
+
+    /// Keys which may author the fuzzed transactions: the seeded wallets,
+    /// the validator and the issuer.
+    pub fn authors(&self) -> Vec<KeyPair> {
+        let mut authors = self.wallets.clone();
+        authors.push(self.kit.validator());
+        authors.push(self.kit.issuer());
+        authors
+    }
+
+    /// Commits `tx` and the transactions broadcasted by the service after it,
+    /// panics if any of them panicked during execution.
+    pub fn execute(&mut self, tx: Signed<RawTransaction>) {
+        self.kit.create_block(vec![tx.clone()]);
+        self.assert_no_panics(&[tx]);
+
+        // Confirmations of the transactions approved by the fuzzed one
+        let broadcasted = self.kit.pool().to_vec();
+        self.kit.create_block(vec![]);
+        self.assert_no_panics(&broadcasted);
+
+        self.kit.rollback();
+    }
+
+    fn assert_no_panics(&self, txs: &[Signed<RawTransaction>]) {
+        for tx in txs {
+            if let Err(error) = self.kit.tx_result(tx).0 {
+                if error.error_type() == TransactionErrorType::Panic {
+                    panic!(
+                        "Transaction {:?} panicked: {:?}",
+                        tx.hash(),
+                        error.description()
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,23 @@
+use super::asset::{Asset, AssetBalance};
+use super::config::{ConfigProposal, ServiceConfig, TransactionFees};
+use super::receipt::Receipt;
 use super::schema::Schema;
-use super::transaction::ApprovedTransaction;
-use super::wallet::Wallet;
+use super::service::SERVICE_ID;
+use super::transaction::{ApprovedTransaction, IncomingTransaction, WalletTransaction};
+use super::wallet::{Signer, Wallet};
 use exonum::api::{Error as ApiError, Result, ServiceApiBuilder, ServiceApiState};
-use exonum::crypto::PublicKey;
+use exonum::blockchain::{
+    Transaction, TransactionContext, TransactionError, TransactionErrorType, TransactionSet,
+};
+use exonum::crypto::{Hash, PublicKey, Signature};
+use exonum::messages::{BinaryForm, Message, ProtocolMessage, RawTransaction, Signed};
+use exonum::storage::Snapshot;
 use serde_derive::{Deserialize, Serialize};
+use std::panic;
 
 #[derive(Serialize, Deserialize)]
 pub struct WalletQuery {
-    pub_key: PublicKey,
+    pub pub_key: PublicKey,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -26,6 +36,177 @@ pub struct TransactionsQuery {
     pub pub_key: PublicKey,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AssetQuery {
+    pub asset_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReceiptQuery {
+    pub tx_hash: Hash,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BlockReceiptsQuery {
+    pub height: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IncomingTransactionsQuery {
+    pub pub_key: PublicKey,
+    pub offset: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncomingTransactions {
+    pub total: u64,
+    pub txs: Vec<IncomingTransaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WalletsQuery {
+    pub from: Option<PublicKey>,
+    pub limit: Option<u64>,
+    pub min_balance: Option<u64>,
+    pub has_signers: Option<bool>,
+    pub name_prefix: Option<String>,
+}
+
+impl WalletsQuery {
+    fn matches(&self, wallet: &Wallet) -> bool {
+        if let Some(min_balance) = self.min_balance {
+            if wallet.balance < min_balance {
+                return false;
+            }
+        }
+
+        if let Some(has_signers) = self.has_signers {
+            if wallet.signers.is_empty() == has_signers {
+                return false;
+            }
+        }
+
+        if let Some(ref name_prefix) = self.name_prefix {
+            if !wallet.name.starts_with(name_prefix.as_str()) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Page of wallets, `next` holds the key to pass as `from` to get the following page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletsPage<W> {
+    pub wallets: Vec<W>,
+    pub next: Option<PublicKey>,
+}
+
+/// Wallet view without embedded pending and approved transactions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletSummary {
+    pub pub_key: PublicKey,
+    pub name: String,
+    pub balance: u64,
+    pub assets: Vec<AssetBalance>,
+    pub signers: Vec<Signer>,
+    pub pending_txs_count: u64,
+    pub txs_count: u64,
+    pub history_len: u64,
+    pub history_hash: Hash,
+}
+
+impl From<Wallet> for WalletSummary {
+    fn from(wallet: Wallet) -> Self {
+        Self {
+            pub_key: wallet.pub_key,
+            name: wallet.name,
+            balance: wallet.balance,
+            assets: wallet.assets,
+            signers: wallet.signers,
+            pending_txs_count: wallet.pending_txs.len() as u64,
+            txs_count: wallet.txs.len() as u64,
+            history_len: wallet.history_len,
+            history_hash: wallet.history_hash,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Supply {
+    pub total_supply: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeesInfo {
+    pub treasury: PublicKey,
+    pub fees: TransactionFees,
+    pub collected: TransactionFees,
+    pub collected_total: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigProposalInfo {
+    pub proposal_hash: Hash,
+    pub proposal: ConfigProposal,
+}
+
+/// Transaction description to be turned into the bytes its author has to sign.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrepareTransactionRequest {
+    pub author: PublicKey,
+    pub transaction: WalletTransaction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PreparedTransaction {
+    pub payload: String,
+}
+
+/// Payload returned by `v1/transactions/prepare` along with its detached signature.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SignedTransaction {
+    pub payload: String,
+    pub signature: Signature,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TransactionResponse {
+    pub tx_hash: Hash,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WalletDiff {
+    pub pub_key: PublicKey,
+    pub before: Option<Wallet>,
+    pub after: Option<Wallet>,
+}
+
+/// Outcome of a transaction execution, mirrors transaction status of the explorer API.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum SimulationStatus {
+    Success,
+    Panic { description: String },
+    Error { code: u8, description: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SimulationResult {
+    pub tx_hash: Hash,
+    pub status: SimulationStatus,
+    pub diffs: Vec<WalletDiff>,
+}
+
+const DEFAULT_PAGE_LIMIT: u64 = 100;
+const MAX_PAGE_LIMIT: u64 = 1000;
+
+fn page_limit(limit: Option<u64>) -> usize {
+    limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize
+}
+
 pub struct Api;
 
 impl Api {
@@ -34,7 +215,20 @@ impl Api {
             .public_scope()
             .endpoint("v1/wallet", Self::get_wallet)
             .endpoint("v1/wallets", Self::get_wallets)
-            .endpoint("v1/wallet/txs", Self::get_approved_txs);
+            .endpoint("v1/wallets/summary", Self::get_wallets_summary)
+            .endpoint("v1/wallet/txs", Self::get_approved_txs)
+            .endpoint("v1/wallet/incoming", Self::get_incoming_txs)
+            .endpoint("v1/asset", Self::get_asset)
+            .endpoint("v1/assets", Self::get_assets)
+            .endpoint("v1/supply", Self::get_supply)
+            .endpoint("v1/config", Self::get_config)
+            .endpoint("v1/config/proposals", Self::get_config_proposals)
+            .endpoint("v1/fees", Self::get_fees)
+            .endpoint("v1/receipt", Self::get_receipt)
+            .endpoint("v1/block/receipts", Self::get_block_receipts)
+            .endpoint_mut("v1/transactions/prepare", Self::prepare_transaction)
+            .endpoint_mut("v1/transactions/submit", Self::submit_transaction)
+            .endpoint_mut("v1/transactions/simulate", Self::simulate_transaction);
     }
 
     pub fn get_wallet(state: &ServiceApiState, query: WalletQuery) -> Result<Wallet> {
@@ -59,12 +253,293 @@ impl Api {
             .map(|wallet| wallet.txs)
     }
 
-    pub fn get_wallets(state: &ServiceApiState, _query: ()) -> Result<Vec<Wallet>> {
+    pub fn get_incoming_txs(
+        state: &ServiceApiState,
+        query: IncomingTransactionsQuery,
+    ) -> Result<IncomingTransactions> {
+        let snapshot = state.snapshot();
+        let schema = Schema::new(snapshot);
+
+        if schema.wallet(&query.pub_key).is_none() {
+            Err(ApiError::NotFound("Wallet not found".to_owned()))?
+        }
+
+        let offset = query.offset.unwrap_or(0);
+        let limit = page_limit(query.limit);
+
+        let idx = schema.incoming_txs(&query.pub_key);
+        let txs = idx.iter_from(offset).take(limit).collect();
+
+        Ok(IncomingTransactions {
+            total: idx.len(),
+            txs,
+        })
+    }
+
+    pub fn get_asset(state: &ServiceApiState, query: AssetQuery) -> Result<Asset> {
+        let snapshot = state.snapshot();
+        let schema = Schema::new(snapshot);
+
+        schema
+            .asset(&query.asset_id)
+            .ok_or_else(|| ApiError::NotFound("Asset not found".to_owned()))
+    }
+
+    pub fn get_assets(state: &ServiceApiState, _query: ()) -> Result<Vec<Asset>> {
+        let snapshot = state.snapshot();
+        let schema = Schema::new(snapshot);
+        let idx = schema.assets();
+        let assets = idx.values().collect();
+
+        Ok(assets)
+    }
+
+    pub fn get_supply(state: &ServiceApiState, _query: ()) -> Result<Supply> {
+        let snapshot = state.snapshot();
+        let schema = Schema::new(snapshot);
+
+        Ok(Supply {
+            total_supply: schema.total_supply(),
+        })
+    }
+
+    pub fn get_config(state: &ServiceApiState, _query: ()) -> Result<ServiceConfig> {
+        let snapshot = state.snapshot();
+        let schema = Schema::new(snapshot);
+
+        Ok(schema.config())
+    }
+
+    pub fn get_fees(state: &ServiceApiState, _query: ()) -> Result<FeesInfo> {
+        let snapshot = state.snapshot();
+        let schema = Schema::new(snapshot);
+        let config = schema.config();
+        let collected = schema.collected_fees();
+
+        Ok(FeesInfo {
+            treasury: config.treasury,
+            fees: config.fees,
+            collected_total: collected.saturating_total(),
+            collected,
+        })
+    }
+
+    pub fn get_config_proposals(
+        state: &ServiceApiState,
+        _query: (),
+    ) -> Result<Vec<ConfigProposalInfo>> {
+        let snapshot = state.snapshot();
+        let schema = Schema::new(snapshot);
+        let proposals = schema
+            .config_proposals()
+            .iter()
+            .map(|(proposal_hash, proposal)| ConfigProposalInfo {
+                proposal_hash,
+                proposal,
+            })
+            .collect();
+
+        Ok(proposals)
+    }
+
+    pub fn get_receipt(state: &ServiceApiState, query: ReceiptQuery) -> Result<Receipt> {
         let snapshot = state.snapshot();
         let schema = Schema::new(snapshot);
+
+        schema
+            .receipt(&query.tx_hash)
+            .ok_or_else(|| ApiError::NotFound("Receipt not found".to_owned()))
+    }
+
+    pub fn get_block_receipts(
+        state: &ServiceApiState,
+        query: BlockReceiptsQuery,
+    ) -> Result<Vec<Receipt>> {
+        let snapshot = state.snapshot();
+        let schema = Schema::new(snapshot);
+        let receipts = schema
+            .block_receipts(query.height)
+            .iter()
+            .filter_map(|tx_hash| schema.receipt(&tx_hash))
+            .collect();
+
+        Ok(receipts)
+    }
+
+    pub fn get_wallets(
+        state: &ServiceApiState,
+        query: WalletsQuery,
+    ) -> Result<WalletsPage<Wallet>> {
+        let snapshot = state.snapshot();
+        let schema = Schema::new(snapshot);
+
+        Ok(Self::wallets_page(&schema, &query))
+    }
+
+    pub fn get_wallets_summary(
+        state: &ServiceApiState,
+        query: WalletsQuery,
+    ) -> Result<WalletsPage<WalletSummary>> {
+        let snapshot = state.snapshot();
+        let schema = Schema::new(snapshot);
+        let page = Self::wallets_page(&schema, &query);
+
+        Ok(WalletsPage {
+            wallets: page.wallets.into_iter().map(WalletSummary::from).collect(),
+            next: page.next,
+        })
+    }
+
+    fn wallets_page<T>(schema: &Schema<T>, query: &WalletsQuery) -> WalletsPage<Wallet>
+    where
+        T: AsRef<Snapshot>,
+    {
         let idx = schema.wallets();
-        let wallets = idx.values().collect();
+        // Empty page would return the same `next` key forever
+        let limit = page_limit(query.limit).max(1);
+        let iter = match query.from {
+            Some(ref from) => idx.iter_from(from),
+            None => idx.iter(),
+        };
+
+        let mut wallets = Vec::new();
+        let mut next = None;
+
+        for (pub_key, wallet) in iter.filter(|(_, wallet)| query.matches(wallet)) {
+            if wallets.len() == limit {
+                next = Some(pub_key);
+                break;
+            }
+            wallets.push(wallet);
+        }
+
+        WalletsPage { wallets, next }
+    }
+
+    pub fn prepare_transaction(
+        _state: &ServiceApiState,
+        query: PrepareTransactionRequest,
+    ) -> Result<PreparedTransaction> {
+        if let WalletTransaction::Confirmation(_) = query.transaction {
+            Err(ApiError::BadRequest(
+                "Confirmation transactions are issued by the service".to_owned(),
+            ))?
+        }
+
+        let raw = RawTransaction::new(SERVICE_ID, query.transaction.into());
+        let (class, tag) = RawTransaction::message_type();
+
+        let mut payload = query.author.as_ref().to_vec();
+        payload.push(class);
+        payload.push(tag);
+        payload.extend(
+            raw.encode()
+                .map_err(|e| ApiError::BadRequest(e.to_string()))?,
+        );
+
+        Ok(PreparedTransaction {
+            payload: hex::encode(payload),
+        })
+    }
+
+    pub fn submit_transaction(
+        state: &ServiceApiState,
+        query: SignedTransaction,
+    ) -> Result<TransactionResponse> {
+        let mut buffer =
+            hex::decode(&query.payload).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        buffer.extend_from_slice(query.signature.as_ref());
+
+        let (signed, _) = Self::decode_transaction(buffer)?;
+        let tx_hash = signed.hash();
+        state.sender().broadcast_transaction(signed)?;
+
+        Ok(TransactionResponse { tx_hash })
+    }
+
+    pub fn simulate_transaction(
+        state: &ServiceApiState,
+        query: TransactionHex,
+    ) -> Result<SimulationResult> {
+        let buffer =
+            hex::decode(&query.tx_body).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        let (signed, transaction) = Self::decode_transaction(buffer)?;
+        let author = signed.author();
+
+        let mut fork = state.blockchain().fork();
+        let keys = transaction.affected_wallets(&author, &Schema::new(&fork));
+        let before: Vec<Option<Wallet>> = {
+            let schema = Schema::new(&fork);
+            keys.iter().map(|key| schema.wallet(key)).collect()
+        };
+
+        // Blockchain of exonum 0.10 executes transactions only as a part of a block, so the
+        // transaction is executed directly. `TransactionContext::new` is `#[doc(hidden)]`, which
+        // is why exonum is pinned to 0.10.3 in Cargo.toml. Panics are caught the same way
+        // the blockchain does it, the fork is dropped anyway.
+        let tx: Box<dyn Transaction> = transaction.into();
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            tx.execute(TransactionContext::new(&mut fork, &signed))
+        }));
+
+        let status = match result {
+            Ok(Ok(())) => SimulationStatus::Success,
+            Ok(Err(e)) => {
+                let error = TransactionError::from(e);
+                let description = error.description().unwrap_or_default().to_owned();
+                match error.error_type() {
+                    TransactionErrorType::Code(code) => {
+                        SimulationStatus::Error { code, description }
+                    }
+                    TransactionErrorType::Panic => SimulationStatus::Panic { description },
+                }
+            }
+            Err(_) => SimulationStatus::Panic {
+                description: "Transaction execution panicked".to_owned(),
+            },
+        };
+
+        let diffs = match status {
+            SimulationStatus::Success => {
+                let schema = Schema::new(&fork);
+                keys.into_iter()
+                    .zip(before)
+                    .filter_map(|(pub_key, before)| {
+                        let after = schema.wallet(&pub_key);
+                        if before == after {
+                            None
+                        } else {
+                            Some(WalletDiff {
+                                pub_key,
+                                before,
+                                after,
+                            })
+                        }
+                    })
+                    .collect()
+            }
+            _ => vec![],
+        };
+
+        Ok(SimulationResult {
+            tx_hash: signed.hash(),
+            status,
+            diffs,
+        })
+    }
+
+    fn decode_transaction(buffer: Vec<u8>) -> Result<(Signed<RawTransaction>, WalletTransaction)> {
+        let message =
+            Message::from_raw_buffer(buffer).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        let signed = RawTransaction::try_from(message)
+            .map_err(|_| ApiError::BadRequest("Message is not a transaction".to_owned()))?;
+
+        if signed.service_id() != SERVICE_ID {
+            Err(ApiError::BadRequest("Unknown service".to_owned()))?
+        }
+        let transaction = WalletTransaction::tx_from_raw(signed.payload().clone())
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
 
-        Ok(wallets)
+        Ok((signed, transaction))
     }
 }
@@ -0,0 +1,43 @@
+use super::proto;
+use exonum::crypto::{self, Hash, PublicKey};
+use exonum_derive::ProtobufConvert;
+use serde_derive::{Deserialize, Serialize};
+
+/// Identifier of the coin every wallet holds in `Wallet::balance`.
+pub const NATIVE_ASSET_ID: &str = "";
+
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Deserialize, Serialize)]
+#[exonum(pb = "proto::Asset")]
+pub struct Asset {
+    pub asset_id: String,
+    pub name: String,
+    pub issuer: PublicKey,
+    pub total_supply: u64,
+}
+
+impl Asset {
+    pub fn new(asset_id: &str, name: &str, &issuer: &PublicKey, total_supply: u64) -> Self {
+        Self {
+            asset_id: asset_id.to_owned(),
+            name: name.to_owned(),
+            issuer,
+            total_supply,
+        }
+    }
+
+    /// Returns the key of the asset definition in the `assets` index.
+    pub fn key(asset_id: &str) -> Hash {
+        crypto::hash(asset_id.as_bytes())
+    }
+
+    pub fn set_total_supply(self, total_supply: u64) -> Self {
+        Self::new(&self.asset_id, &self.name, &self.issuer, total_supply)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Deserialize, Serialize)]
+#[exonum(pb = "proto::AssetBalance")]
+pub struct AssetBalance {
+    pub asset_id: String,
+    pub amount: u64,
+}
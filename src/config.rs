@@ -0,0 +1,213 @@
+use super::proto;
+use exonum::crypto::PublicKey;
+use exonum_derive::ProtobufConvert;
+use failure::{bail, Error};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use toml::Value;
+
+/// Key of the service config block in `NodeConfig::services_configs`.
+pub const CONFIG_KEY: &str = "cryptocurrency";
+
+/// Service parameters, set at genesis and changed by validators voting.
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Deserialize, Serialize)]
+#[exonum(pb = "proto::ServiceConfig")]
+pub struct ServiceConfig {
+    /// Name the service API is mounted under.
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+    /// Amount of native coins credited to every created wallet.
+    #[serde(default)]
+    pub initial_balance: u64,
+    /// Wallet allowed to mint and burn native coins.
+    pub issuer: PublicKey,
+    /// Share of the wallet signers, as `numerator / denominator`, required to approve a transaction.
+    #[serde(default = "default_approval_numerator")]
+    pub approval_numerator: u64,
+    #[serde(default = "default_approval_denominator")]
+    pub approval_denominator: u64,
+    #[serde(default = "default_max_signers")]
+    pub max_signers: u64,
+    #[serde(default = "default_max_pending_txs")]
+    pub max_pending_txs: u64,
+    /// Maximum number of signer groups of a wallet.
+    #[serde(default = "default_max_groups")]
+    pub max_groups: u64,
+    #[serde(default)]
+    pub fees: TransactionFees,
+    /// Wallet collected fees are paid to.
+    pub treasury: PublicKey,
+}
+
+/// Native coin amounts charged from the transaction author, per transaction type.
+///
+/// Also used for the totals of collected fees.
+#[derive(Clone, Debug, Default, PartialEq, ProtobufConvert, Deserialize, Serialize)]
+#[exonum(pb = "proto::TransactionFees")]
+#[serde(default)]
+pub struct TransactionFees {
+    pub create_wallet: u64,
+    pub add_signer: u64,
+    pub transfer: u64,
+    pub sign: u64,
+    pub issue_asset: u64,
+    pub mint: u64,
+    pub burn: u64,
+    pub set_spending_limit: u64,
+    pub batch_transfer: u64,
+    pub accept_signer_invite: u64,
+    pub set_signer_group: u64,
+    pub revoke_approval: u64,
+}
+
+impl TransactionFees {
+    /// Returns the sum of the fees, or `None` if it overflows.
+    pub fn total(&self) -> Option<u64> {
+        self.amounts()
+            .iter()
+            .try_fold(0u64, |total, fee| total.checked_add(*fee))
+    }
+
+    /// Returns the sum of the fees, saturating at the numeric bound.
+    pub fn saturating_total(&self) -> u64 {
+        self.amounts()
+            .iter()
+            .fold(0u64, |total, fee| total.saturating_add(*fee))
+    }
+
+    fn amounts(&self) -> [u64; 12] {
+        [
+            self.create_wallet,
+            self.add_signer,
+            self.transfer,
+            self.sign,
+            self.issue_asset,
+            self.mint,
+            self.burn,
+            self.set_spending_limit,
+            self.batch_transfer,
+            self.accept_signer_invite,
+            self.set_signer_group,
+            self.revoke_approval,
+        ]
+    }
+}
+
+fn default_service_name() -> String {
+    CONFIG_KEY.to_owned()
+}
+
+fn default_approval_numerator() -> u64 {
+    2
+}
+
+fn default_approval_denominator() -> u64 {
+    3
+}
+
+fn default_max_signers() -> u64 {
+    16
+}
+
+fn default_max_pending_txs() -> u64 {
+    64
+}
+
+fn default_max_groups() -> u64 {
+    8
+}
+
+impl ServiceConfig {
+    pub fn new(&issuer: &PublicKey) -> Self {
+        Self {
+            service_name: default_service_name(),
+            initial_balance: 0,
+            issuer,
+            approval_numerator: default_approval_numerator(),
+            approval_denominator: default_approval_denominator(),
+            max_signers: default_max_signers(),
+            max_pending_txs: default_max_pending_txs(),
+            max_groups: default_max_groups(),
+            fees: TransactionFees::default(),
+            treasury: issuer,
+        }
+    }
+
+    /// Reads the service config block from the node `services_configs`.
+    pub fn from_services_configs(configs: &BTreeMap<String, Value>) -> Result<Self, Error> {
+        let value = match configs.get(CONFIG_KEY) {
+            Some(value) => value.clone(),
+            None => bail!("Missing `{}` block in services configs", CONFIG_KEY),
+        };
+
+        let config: Self = value.try_into()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.service_name.is_empty() {
+            bail!("Service name is empty");
+        }
+        if self.approval_denominator == 0 || self.approval_numerator > self.approval_denominator {
+            bail!(
+                "Invalid approval threshold {}/{}",
+                self.approval_numerator,
+                self.approval_denominator
+            );
+        }
+        if self.max_groups == 0 {
+            bail!("Maximum number of signer groups is zero");
+        }
+        if self.fees.total().is_none() {
+            bail!("Sum of transaction fees overflows");
+        }
+        // Created wallet pays its fee from the initial balance
+        if self.fees.create_wallet > self.initial_balance {
+            bail!(
+                "Wallet creation fee {} exceeds initial balance {}",
+                self.fees.create_wallet,
+                self.initial_balance
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the approval weight, including the initiator one, required
+    /// to execute a transaction of the wallet with signers weighing `total_weight`.
+    pub fn required_weight(&self, total_weight: u64) -> u64 {
+        let required = u128::from(self.approval_numerator) * u128::from(total_weight)
+            / u128::from(self.approval_denominator);
+        required as u64
+    }
+}
+
+/// Service config change proposed by a validator.
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Deserialize, Serialize)]
+#[exonum(pb = "proto::ConfigProposal")]
+pub struct ConfigProposal {
+    pub config: ServiceConfig,
+    /// Height of the first block executed with the proposed config.
+    pub activation_height: u64,
+    pub proposer: PublicKey,
+    /// Validators voted for the proposal, including the proposer.
+    pub votes: Vec<PublicKey>,
+}
+
+impl ConfigProposal {
+    pub fn new(config: ServiceConfig, activation_height: u64, &proposer: &PublicKey) -> Self {
+        Self {
+            config,
+            activation_height,
+            proposer,
+            votes: vec![proposer],
+        }
+    }
+
+    pub fn add_vote(self, &validator: &PublicKey) -> Self {
+        let mut votes = self.votes.clone();
+        votes.push(validator);
+
+        Self { votes, ..self }
+    }
+}
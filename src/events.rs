@@ -0,0 +1,497 @@
+use super::asset::NATIVE_ASSET_ID;
+use super::schema::Schema;
+use super::service::SERVICE_ID;
+use super::transaction::{PendingKind, WalletTransaction};
+use actix::{
+    fut, Actor, ActorContext, ActorFuture, Addr, Arbiter, AsyncContext, Context,
+    ContextFutureSpawner, Handler, Recipient, Running, StreamHandler, WrapFuture,
+};
+use actix_web::{http, ws};
+use exonum::api::backends::actix::{FutureResponse, HttpRequest, RawHandler, RequestHandler};
+use exonum::api::{ServiceApiBackend, ServiceApiBuilder, ServiceApiState};
+use exonum::blockchain::{Schema as BlockchainSchema, TransactionSet};
+use exonum::crypto::{Hash, PublicKey};
+use exonum::helpers::Height;
+use exonum::storage::Snapshot;
+use futures::IntoFuture;
+use hex::FromHex;
+use serde_derive::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use url::form_urlencoded;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WalletEvent {
+    WalletCreated {
+        wallet: PublicKey,
+        name: String,
+    },
+    SignerInvited {
+        wallet: PublicKey,
+        signer: PublicKey,
+    },
+    SignerAdded {
+        wallet: PublicKey,
+        signer: PublicKey,
+    },
+    SignerGroupSet {
+        wallet: PublicKey,
+        name: String,
+        members: Vec<PublicKey>,
+        threshold: u64,
+    },
+    PendingCreated {
+        wallet: PublicKey,
+        tx_hash: Hash,
+        kind: PendingKind,
+        recipient: PublicKey,
+        asset_id: String,
+        amount: u64,
+    },
+    ApprovalAdded {
+        wallet: PublicKey,
+        tx_hash: Hash,
+        signer: PublicKey,
+    },
+    ApprovalRevoked {
+        wallet: PublicKey,
+        tx_hash: Hash,
+        signer: PublicKey,
+    },
+    TransferConfirmed {
+        wallet: PublicKey,
+        tx_hash: Hash,
+        kind: PendingKind,
+        recipient: PublicKey,
+        asset_id: String,
+        amount: u64,
+    },
+    BalanceChanged {
+        wallet: PublicKey,
+        asset_id: String,
+        balance: u64,
+    },
+}
+
+/// Event as it is sent to subscribers.
+#[derive(Debug, Clone, Serialize)]
+pub struct EventMessage {
+    pub height: u64,
+    pub tx_hash: Hash,
+    pub event: WalletEvent,
+}
+
+/// Event along with the keys it concerns.
+#[derive(Debug, Clone)]
+struct Notification {
+    wallets: Vec<PublicKey>,
+    signers: Vec<PublicKey>,
+    message: String,
+}
+
+impl Notification {
+    fn new<T>(schema: &Schema<T>, message: &EventMessage) -> Self
+    where
+        T: AsRef<Snapshot>,
+    {
+        let (wallets, mut signers) = match message.event {
+            WalletEvent::PendingCreated {
+                wallet, recipient, ..
+            }
+            | WalletEvent::TransferConfirmed {
+                wallet, recipient, ..
+            } => (vec![wallet, recipient], vec![]),
+            WalletEvent::SignerInvited { wallet, signer }
+            | WalletEvent::SignerAdded { wallet, signer }
+            | WalletEvent::ApprovalAdded { wallet, signer, .. }
+            | WalletEvent::ApprovalRevoked { wallet, signer, .. } => (vec![wallet], vec![signer]),
+            WalletEvent::WalletCreated { wallet, .. }
+            | WalletEvent::SignerGroupSet { wallet, .. }
+            | WalletEvent::BalanceChanged { wallet, .. } => (vec![wallet], vec![]),
+        };
+
+        if let Some(wallet) = schema.wallet(&wallets[0]) {
+            signers.extend(wallet.signers.iter().map(|item| item.pub_key));
+        }
+
+        Self {
+            wallets,
+            signers,
+            message: serde_json::to_string(message).expect("Couldn't serialize event"),
+        }
+    }
+}
+
+/// Collects wallet events produced by the successfully executed transactions of the block.
+fn block_events(snapshot: &dyn Snapshot, height: Height) -> Vec<EventMessage> {
+    let blockchain_schema = BlockchainSchema::new(snapshot);
+    let schema = Schema::new(snapshot);
+    let transactions = blockchain_schema.transactions();
+    let results = blockchain_schema.transaction_results();
+    let mut events = Vec::new();
+
+    for tx_hash in blockchain_schema.block_transactions(height).iter() {
+        match results.get(&tx_hash) {
+            Some(ref result) if result.0.is_ok() => {}
+            _ => continue,
+        }
+        let signed = match transactions.get(&tx_hash) {
+            Some(signed) if signed.service_id() == SERVICE_ID => signed,
+            _ => continue,
+        };
+        let author = signed.author();
+        let transaction = match WalletTransaction::tx_from_raw(signed.payload().clone()) {
+            Ok(tx) => tx,
+            Err(_) => continue,
+        };
+
+        let mut push = |event| {
+            events.push(EventMessage {
+                height: height.0,
+                tx_hash,
+                event,
+            })
+        };
+
+        match transaction {
+            WalletTransaction::CreateWallet(tx) => push(WalletEvent::WalletCreated {
+                wallet: author,
+                name: tx.name,
+            }),
+            WalletTransaction::AddSigner(tx) => push(WalletEvent::SignerInvited {
+                wallet: author,
+                signer: tx.signer,
+            }),
+            WalletTransaction::AcceptSignerInvite(tx) => push(WalletEvent::SignerAdded {
+                wallet: tx.wallet,
+                signer: author,
+            }),
+            WalletTransaction::SetSignerGroup(tx) => push(WalletEvent::SignerGroupSet {
+                wallet: author,
+                name: tx.name,
+                members: tx.members,
+                threshold: tx.threshold,
+            }),
+            WalletTransaction::Transfer(tx) => push(WalletEvent::PendingCreated {
+                wallet: author,
+                tx_hash,
+                kind: PendingKind::Transfer,
+                recipient: tx.recipient,
+                asset_id: tx.asset_id,
+                amount: tx.amount,
+            }),
+            WalletTransaction::Sign(tx) => push(WalletEvent::ApprovalAdded {
+                wallet: tx.origin,
+                tx_hash: tx.tx_hash,
+                signer: author,
+            }),
+            WalletTransaction::RevokeApproval(tx) => push(WalletEvent::ApprovalRevoked {
+                wallet: tx.origin,
+                tx_hash: tx.tx_hash,
+                signer: author,
+            }),
+            WalletTransaction::Confirmation(tx) => {
+                let approved = schema.wallet(&tx.sender).and_then(|wallet| {
+                    wallet
+                        .txs
+                        .into_iter()
+                        .find(|item| item.tx_hash == tx.tx_hash)
+                });
+
+                if let Some(approved) = approved {
+                    // Batch transfer is reported as a transfer per payment
+                    let payments = match approved.kind {
+                        PendingKind::BatchTransfer => approved
+                            .payments
+                            .iter()
+                            .map(|item| (item.recipient, item.amount))
+                            .collect(),
+                        _ => vec![(approved.recipient, approved.amount)],
+                    };
+
+                    for (recipient, amount) in payments {
+                        push(WalletEvent::TransferConfirmed {
+                            wallet: tx.sender,
+                            tx_hash: tx.tx_hash,
+                            kind: approved.kind,
+                            recipient,
+                            asset_id: approved.asset_id.clone(),
+                            amount,
+                        });
+                    }
+                }
+            }
+            WalletTransaction::Mint(tx) => push(WalletEvent::PendingCreated {
+                wallet: author,
+                tx_hash,
+                kind: PendingKind::Mint,
+                recipient: author,
+                asset_id: NATIVE_ASSET_ID.to_owned(),
+                amount: tx.amount,
+            }),
+            WalletTransaction::Burn(tx) => push(WalletEvent::PendingCreated {
+                wallet: author,
+                tx_hash,
+                kind: PendingKind::Burn,
+                recipient: author,
+                asset_id: NATIVE_ASSET_ID.to_owned(),
+                amount: tx.amount,
+            }),
+            WalletTransaction::SetSpendingLimit(tx) => push(WalletEvent::PendingCreated {
+                wallet: author,
+                tx_hash,
+                kind: PendingKind::SetSpendingLimit,
+                recipient: author,
+                asset_id: NATIVE_ASSET_ID.to_owned(),
+                amount: tx.amount,
+            }),
+            WalletTransaction::BatchTransfer(tx) => push(WalletEvent::PendingCreated {
+                wallet: author,
+                tx_hash,
+                kind: PendingKind::BatchTransfer,
+                recipient: author,
+                amount: tx.payments.iter().map(|item| item.amount).sum(),
+                asset_id: tx.asset_id,
+            }),
+            WalletTransaction::IssueAsset(_)
+            | WalletTransaction::ProposeServiceConfig(_)
+            | WalletTransaction::VoteServiceConfig(_) => {}
+        }
+
+        // Fees move balances of the author and the treasury, so the changes come from the receipt
+        if let Some(receipt) = schema.receipt(&tx_hash) {
+            for change in receipt.balance_changes {
+                push(WalletEvent::BalanceChanged {
+                    wallet: change.wallet,
+                    asset_id: change.asset_id,
+                    balance: change.after,
+                });
+            }
+        }
+    }
+
+    events
+}
+
+/// Keys the subscriber is interested in, empty subscription receives every event.
+#[derive(Debug, Clone, Default)]
+struct Subscription {
+    wallets: Vec<PublicKey>,
+    signers: Vec<PublicKey>,
+}
+
+impl Subscription {
+    fn from_request(request: &HttpRequest) -> Result<Self, String> {
+        let mut subscription = Self::default();
+
+        // Query map keeps one value of a repeated parameter, so the raw query is parsed
+        for (name, value) in form_urlencoded::parse(request.query_string().as_bytes()) {
+            let key = PublicKey::from_hex(&*value).map_err(|e| format!("{}: {}", name, e))?;
+            match &*name {
+                "wallet" => subscription.wallets.push(key),
+                "signer" => subscription.signers.push(key),
+                _ => Err(format!("Unknown subscription parameter `{}`", name))?,
+            }
+        }
+
+        Ok(subscription)
+    }
+
+    fn matches(&self, notification: &Notification) -> bool {
+        if self.wallets.is_empty() && self.signers.is_empty() {
+            return true;
+        }
+
+        self.wallets
+            .iter()
+            .any(|key| notification.wallets.contains(key))
+            || self
+                .signers
+                .iter()
+                .any(|key| notification.signers.contains(key))
+    }
+}
+
+struct Message(String);
+
+impl actix::Message for Message {
+    type Result = ();
+}
+
+struct Subscribe {
+    address: Recipient<Message>,
+    subscription: Subscription,
+}
+
+impl actix::Message for Subscribe {
+    type Result = usize;
+}
+
+struct Unsubscribe {
+    id: usize,
+}
+
+impl actix::Message for Unsubscribe {
+    type Result = ();
+}
+
+struct Broadcast {
+    notifications: Vec<Notification>,
+}
+
+impl actix::Message for Broadcast {
+    type Result = ();
+}
+
+#[derive(Default)]
+struct Server {
+    subscribers: HashMap<usize, (Recipient<Message>, Subscription)>,
+    next_id: usize,
+}
+
+impl Actor for Server {
+    type Context = Context<Self>;
+}
+
+impl Handler<Subscribe> for Server {
+    type Result = usize;
+
+    fn handle(&mut self, msg: Subscribe, _ctx: &mut Self::Context) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.subscribers.insert(id, (msg.address, msg.subscription));
+
+        id
+    }
+}
+
+impl Handler<Unsubscribe> for Server {
+    type Result = ();
+
+    fn handle(&mut self, Unsubscribe { id }: Unsubscribe, _ctx: &mut Self::Context) {
+        self.subscribers.remove(&id);
+    }
+}
+
+impl Handler<Broadcast> for Server {
+    type Result = ();
+
+    fn handle(&mut self, Broadcast { notifications }: Broadcast, _ctx: &mut Self::Context) {
+        for (address, subscription) in self.subscribers.values() {
+            for notification in &notifications {
+                if subscription.matches(notification) {
+                    let _ = address.do_send(Message(notification.message.clone()));
+                }
+            }
+        }
+    }
+}
+
+struct Session {
+    id: usize,
+    server_address: Addr<Server>,
+    subscription: Subscription,
+}
+
+impl Actor for Session {
+    type Context = ws::WebsocketContext<Self, ServiceApiState>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.server_address
+            .send(Subscribe {
+                address: ctx.address().recipient(),
+                subscription: self.subscription.clone(),
+            })
+            .into_actor(self)
+            .then(|response, actor, context| {
+                match response {
+                    Ok(id) => actor.id = id,
+                    _ => context.stop(),
+                }
+                fut::ok(())
+            })
+            .wait(ctx);
+    }
+
+    fn stopping(&mut self, _ctx: &mut Self::Context) -> Running {
+        self.server_address.do_send(Unsubscribe { id: self.id });
+        Running::Stop
+    }
+}
+
+impl Handler<Message> for Session {
+    type Result = ();
+
+    fn handle(&mut self, msg: Message, ctx: &mut Self::Context) {
+        ctx.text(msg.0);
+    }
+}
+
+impl StreamHandler<ws::Message, ws::ProtocolError> for Session {
+    fn handle(&mut self, msg: ws::Message, ctx: &mut Self::Context) {
+        match msg {
+            ws::Message::Ping(msg) => ctx.pong(&msg),
+            ws::Message::Close(_) => ctx.stop(),
+            _ => {}
+        }
+    }
+}
+
+/// Delivers wallet events of the committed blocks to WebSocket subscribers.
+///
+/// Events server is started on the first subscription, blocks committed before
+/// that are not processed.
+#[derive(Clone, Default)]
+pub struct EventHub {
+    server: Arc<Mutex<Option<Addr<Server>>>>,
+}
+
+impl EventHub {
+    pub fn wire(&self, builder: &mut ServiceApiBuilder) {
+        let server = self.server.clone();
+        let index = move |request: HttpRequest| -> FutureResponse {
+            let subscription = match Subscription::from_request(&request) {
+                Ok(subscription) => subscription,
+                Err(e) => return Box::new(Err(actix_web::error::ErrorBadRequest(e)).into_future()),
+            };
+
+            let server_address = server
+                .lock()
+                .expect("Expected mutex lock")
+                .get_or_insert_with(|| Arbiter::start(|_| Server::default()))
+                .clone();
+            let session = Session {
+                id: 0,
+                server_address,
+                subscription,
+            };
+
+            Box::new(ws::start(&request, session).into_future())
+        };
+
+        builder
+            .public_scope()
+            .web_backend()
+            .raw_handler(RequestHandler {
+                name: "v1/wallets/subscribe".to_owned(),
+                method: http::Method::GET,
+                inner: Arc::new(index) as Arc<RawHandler>,
+            });
+    }
+
+    pub fn notify(&self, snapshot: &dyn Snapshot, height: Height) {
+        let server = self.server.lock().expect("Expected mutex lock");
+        if let Some(ref address) = *server {
+            let schema = Schema::new(snapshot);
+            let notifications: Vec<_> = block_events(snapshot, height)
+                .iter()
+                .map(|message| Notification::new(&schema, message))
+                .collect();
+
+            if !notifications.is_empty() {
+                address.do_send(Broadcast { notifications });
+            }
+        }
+    }
+}
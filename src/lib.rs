@@ -0,0 +1,15 @@
+extern crate exonum;
+#[macro_use]
+extern crate exonum_derive;
+
+pub mod api;
+pub mod asset;
+pub mod config;
+pub mod events;
+pub mod node;
+pub mod proto;
+pub mod receipt;
+pub mod schema;
+pub mod service;
+pub mod transaction;
+pub mod wallet;
@@ -1,24 +1,20 @@
-extern crate exonum;
-#[macro_use]
-extern crate exonum_derive;
 use exonum::node::Node;
 use exonum::storage::MemoryDB;
-
-mod api;
-mod node;
-mod proto;
-mod schema;
-mod service;
-mod transaction;
-mod wallet;
+use exonum_multisig::{config, node, service};
+use std::path::PathBuf;
 
 fn main() {
     exonum::helpers::init_logger().unwrap();
 
+    let service_config_path = std::env::args().nth(1).map(PathBuf::from);
+    let node_config = node::get_node_config(service_config_path.as_deref()).unwrap();
+    let service_config =
+        config::ServiceConfig::from_services_configs(&node_config.services_configs).unwrap();
+
     let node = Node::new(
         MemoryDB::new(),
-        vec![Box::new(service::Service)],
-        node::get_node_config(),
+        vec![Box::new(service::Service::new(service_config))],
+        node_config,
         None,
     );
 
@@ -1,7 +1,29 @@
+use super::config::{ServiceConfig, CONFIG_KEY};
 use exonum::blockchain::{config::ValidatorKeys, GenesisConfig};
+use exonum::crypto::PublicKey;
 use exonum::node::{NodeApiConfig, NodeConfig};
+use failure::Error;
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+use toml::Value;
 
-pub fn get_node_config() -> NodeConfig {
+/// Builds the service config block, fields of the JSON file at `path` override the defaults.
+fn get_service_config(issuer: &PublicKey, path: Option<&Path>) -> Result<Value, Error> {
+    let mut config = serde_json::to_value(ServiceConfig::new(issuer))?;
+
+    if let Some(path) = path {
+        let overrides: BTreeMap<String, JsonValue> = serde_json::from_reader(File::open(path)?)?;
+        for (key, value) in overrides {
+            config[key] = value;
+        }
+    }
+
+    Ok(Value::try_from(config)?)
+}
+
+pub fn get_node_config(service_config_path: Option<&Path>) -> Result<NodeConfig, Error> {
     let (consensus_public_key, consensus_secret_key) = exonum::crypto::gen_keypair();
     let (service_public_key, service_secret_key) = exonum::crypto::gen_keypair();
 
@@ -19,7 +41,13 @@ pub fn get_node_config() -> NodeConfig {
         ..Default::default()
     };
 
-    NodeConfig {
+    let mut services_configs = BTreeMap::new();
+    services_configs.insert(
+        CONFIG_KEY.to_owned(),
+        get_service_config(&service_public_key, service_config_path)?,
+    );
+
+    Ok(NodeConfig {
         listen_address: "0.0.0.0:2000".parse().unwrap(),
         external_address: "0.0.0.0:2000".parse().unwrap(),
         service_public_key,
@@ -33,6 +61,6 @@ pub fn get_node_config() -> NodeConfig {
         api: api_cfg,
         thread_pool_size: Default::default(),
         mempool: Default::default(),
-        services_configs: Default::default(),
-    }
+        services_configs,
+    })
 }
@@ -2,8 +2,12 @@
 #![allow(renamed_and_removed_lints)]
 
 pub use self::schemes::{
-    AddSignerTx, ApprovedTransaction, ConfirmationTx, CreateWalletTx, PendingTransaction, SignTx,
-    TransferTx, Wallet,
+    AcceptSignerInviteTx, AddSignerTx, Approval, ApprovedTransaction, Asset, AssetBalance,
+    BalanceChange, BatchTransferTx, BurnTx, ConfigProposal, ConfirmationTx, CreateWalletTx,
+    GroupQuorum, IncomingTransaction, IssueAssetTx, MintTx, Payment, PendingKind,
+    PendingTransaction, ProposeServiceConfigTx, Receipt, Rejection, RevokeApprovalTx,
+    ServiceConfig, SetSignerGroupTx, SetSpendingLimitTx, SignTx, Signer, SignerGroup, Spending,
+    SpendingLimit, TransactionFees, TransferTx, VoteServiceConfigTx, Wallet,
 };
 
 include!(concat!(env!("OUT_DIR"), "/protobuf_mod.rs"));
@@ -0,0 +1,67 @@
+use super::proto;
+use exonum::crypto::{Hash, PublicKey};
+use exonum_derive::ProtobufConvert;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Deserialize, Serialize)]
+#[exonum(pb = "proto::BalanceChange")]
+pub struct BalanceChange {
+    pub wallet: PublicKey,
+    pub asset_id: String,
+    pub before: u64,
+    pub after: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Deserialize, Serialize)]
+#[exonum(pb = "proto::Approval")]
+pub struct Approval {
+    pub wallet: PublicKey,
+    pub tx_hash: Hash,
+    pub signer: PublicKey,
+}
+
+/// Pending transaction dropped by its confirmation, as it can't be executed anymore.
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Deserialize, Serialize)]
+#[exonum(pb = "proto::Rejection")]
+pub struct Rejection {
+    pub wallet: PublicKey,
+    pub tx_hash: Hash,
+    /// Code and description of the `TxError` the transaction failed with.
+    pub code: u32,
+    pub description: String,
+}
+
+/// Effects of a successfully executed transaction.
+///
+/// `thresholds_reached` holds hashes of pending transactions that got enough approvals,
+/// `awaiting_created` and `awaiting_consumed` hold keys of the `awaiting_txs` entries,
+/// the latter include entries cleared by revoked approvals.
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Deserialize, Serialize)]
+#[exonum(pb = "proto::Receipt")]
+pub struct Receipt {
+    pub tx_hash: Hash,
+    pub height: u64,
+    pub balance_changes: Vec<BalanceChange>,
+    pub approvals: Vec<Approval>,
+    pub thresholds_reached: Vec<Hash>,
+    pub awaiting_created: Vec<Hash>,
+    pub awaiting_consumed: Vec<Hash>,
+    pub revoked_approvals: Vec<Approval>,
+    pub rejections: Vec<Rejection>,
+}
+
+impl Receipt {
+    pub fn new(&tx_hash: &Hash, height: u64) -> Self {
+        Self {
+            tx_hash,
+            height,
+            balance_changes: vec![],
+            approvals: vec![],
+            thresholds_reached: vec![],
+            awaiting_created: vec![],
+            awaiting_consumed: vec![],
+            revoked_approvals: vec![],
+            rejections: vec![],
+        }
+    }
+}
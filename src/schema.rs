@@ -1,7 +1,14 @@
-use super::transaction::{ApprovedTransaction, PendingTransaction, SignTx};
-use super::wallet::Wallet;
-use exonum::crypto::{Hash, PublicKey};
-use exonum::storage::{Fork, ProofListIndex, ProofMapIndex, Snapshot};
+use super::asset::{Asset, NATIVE_ASSET_ID};
+use super::config::{ConfigProposal, ServiceConfig, TransactionFees};
+use super::receipt::{Approval, BalanceChange, Receipt, Rejection};
+use super::transaction::{
+    ApprovedTransaction, IncomingTransaction, PendingTransaction, SignTx, TxError,
+};
+use super::wallet::{total_weight, Signer, SignerGroup, Spending, SpendingLimit, Wallet};
+use exonum::blockchain::Schema as BlockchainSchema;
+use exonum::crypto::{CryptoHash, Hash, PublicKey};
+use exonum::node::State;
+use exonum::storage::{Entry, Fork, ProofListIndex, ProofMapIndex, Snapshot};
 
 pub struct Schema<T> {
     view: T,
@@ -23,9 +30,93 @@ where
         self.wallets().get(pub_key)
     }
 
+    /// Hashes of the transactions which changed the wallet, `Wallet::history_len` is its length.
+    pub fn wallet_history(&self, public_key: &PublicKey) -> ProofListIndex<&T, Hash> {
+        ProofListIndex::new_in_family("history", public_key, &self.view)
+    }
+
+    /// Pending transactions awaiting confirmation, keyed by their hash.
     pub fn awaiting_txs(&self) -> ProofMapIndex<&T, Hash, SignTx> {
         ProofMapIndex::new("awaiting_txs", &self.view)
     }
+
+    pub fn incoming_txs(&self, recipient: &PublicKey) -> ProofListIndex<&T, IncomingTransaction> {
+        ProofListIndex::new_in_family("incoming_txs", recipient, &self.view)
+    }
+
+    pub fn assets(&self) -> ProofMapIndex<&T, Hash, Asset> {
+        ProofMapIndex::new("assets", &self.view)
+    }
+
+    pub fn asset(&self, asset_id: &str) -> Option<Asset> {
+        self.assets().get(&Asset::key(asset_id))
+    }
+
+    pub fn receipts(&self) -> ProofMapIndex<&T, Hash, Receipt> {
+        ProofMapIndex::new("receipts", &self.view)
+    }
+
+    pub fn receipt(&self, tx_hash: &Hash) -> Option<Receipt> {
+        self.receipts().get(tx_hash)
+    }
+
+    pub fn block_receipts(&self, height: u64) -> ProofListIndex<&T, Hash> {
+        ProofListIndex::new_in_family("block_receipts", &height, &self.view)
+    }
+
+    /// Returns the service configuration, written once at genesis.
+    pub fn config(&self) -> ServiceConfig {
+        Entry::new("config", &self.view)
+            .get()
+            .expect("Service config is not initialized")
+    }
+
+    /// Returns hashes of the service config and its proposals, so validators can't
+    /// disagree on the fees or limits without diverging state hashes.
+    pub fn state_hash(&self) -> Vec<Hash> {
+        vec![self.config().hash(), self.config_proposals().merkle_root()]
+    }
+
+    pub fn config_proposals(&self) -> ProofMapIndex<&T, Hash, ConfigProposal> {
+        ProofMapIndex::new("config_proposals", &self.view)
+    }
+
+    pub fn config_proposal(&self, proposal_hash: &Hash) -> Option<ConfigProposal> {
+        self.config_proposals().get(proposal_hash)
+    }
+
+    /// Returns the approval weight, including the initiator one, required
+    /// to execute a transaction proposed by the wallet now.
+    pub fn required_weight(&self, wallet: &Wallet) -> u64 {
+        self.config().required_weight(total_weight(&wallet.signers))
+    }
+
+    /// Returns service keys of the actual validators.
+    pub fn validators(&self) -> Vec<PublicKey> {
+        BlockchainSchema::new(self.view.as_ref())
+            .actual_configuration()
+            .validator_keys
+            .iter()
+            .map(|keys| keys.service_key)
+            .collect()
+    }
+
+    /// Returns the height of the block being executed.
+    pub fn current_height(&self) -> u64 {
+        BlockchainSchema::new(self.view.as_ref()).height().next().0
+    }
+
+    /// Returns totals of the fees collected since genesis.
+    pub fn collected_fees(&self) -> TransactionFees {
+        Entry::new("collected_fees", &self.view)
+            .get()
+            .unwrap_or_default()
+    }
+
+    /// Returns the total amount of native coins in circulation.
+    pub fn total_supply(&self) -> u64 {
+        Entry::new("total_supply", &self.view).get().unwrap_or(0)
+    }
 }
 
 impl<'a> Schema<&'a mut Fork> {
@@ -37,6 +128,81 @@ impl<'a> Schema<&'a mut Fork> {
         ProofMapIndex::new("awaiting_txs", &mut self.view)
     }
 
+    pub fn incoming_txs_mut(
+        &mut self,
+        recipient: &PublicKey,
+    ) -> ProofListIndex<&mut Fork, IncomingTransaction> {
+        ProofListIndex::new_in_family("incoming_txs", recipient, &mut self.view)
+    }
+
+    pub fn assets_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Asset> {
+        ProofMapIndex::new("assets", &mut self.view)
+    }
+
+    pub fn receipts_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Receipt> {
+        ProofMapIndex::new("receipts", &mut self.view)
+    }
+
+    pub fn block_receipts_mut(&mut self, height: u64) -> ProofListIndex<&mut Fork, Hash> {
+        ProofListIndex::new_in_family("block_receipts", &height, &mut self.view)
+    }
+
+    pub fn config_mut(&mut self) -> Entry<&mut Fork, ServiceConfig> {
+        Entry::new("config", &mut self.view)
+    }
+
+    pub fn config_proposals_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, ConfigProposal> {
+        ProofMapIndex::new("config_proposals", &mut self.view)
+    }
+
+    pub fn collected_fees_mut(&mut self) -> Entry<&mut Fork, TransactionFees> {
+        Entry::new("collected_fees", &mut self.view)
+    }
+
+    pub fn total_supply_mut(&mut self) -> Entry<&mut Fork, u64> {
+        Entry::new("total_supply", &mut self.view)
+    }
+
+    /// Applies `update` to the receipt of the transaction, creating the receipt
+    /// in the block being executed if it doesn't exist yet.
+    /// Records the receipt of the executed transaction, even if it has no effects yet.
+    pub fn create_receipt(&mut self, transaction: &Hash) {
+        self.update_receipt(transaction, |_| {});
+    }
+
+    fn update_receipt<F>(&mut self, transaction: &Hash, update: F)
+    where
+        F: FnOnce(&mut Receipt),
+    {
+        let mut receipt = match self.receipts_mut().get(transaction) {
+            Some(receipt) => receipt,
+            None => {
+                let height = self.current_height();
+                self.block_receipts_mut(height).push(*transaction);
+                Receipt::new(transaction, height)
+            }
+        };
+
+        update(&mut receipt);
+        self.receipts_mut().put(transaction, receipt);
+    }
+
+    fn record_balance_change(
+        &mut self,
+        wallet: &Wallet,
+        asset_id: &str,
+        after: u64,
+        transaction: &Hash,
+    ) {
+        let change = BalanceChange {
+            wallet: wallet.pub_key,
+            asset_id: asset_id.to_owned(),
+            before: wallet.balance_of(asset_id),
+            after,
+        };
+        self.update_receipt(transaction, |receipt| receipt.balance_changes.push(change));
+    }
+
     pub fn wallet_history_mut(
         &mut self,
         public_key: &PublicKey,
@@ -44,7 +210,17 @@ impl<'a> Schema<&'a mut Fork> {
         ProofListIndex::new_in_family("history", public_key, &mut self.view)
     }
 
-    pub fn create_wallet(&mut self, key: &PublicKey, name: &str, transaction: &Hash) {
+    pub fn create_wallet(
+        &mut self,
+        key: &PublicKey,
+        name: &str,
+        transaction: &Hash,
+    ) -> Result<(), TxError> {
+        let initial_balance = self.config().initial_balance;
+        let total_supply = self
+            .total_supply()
+            .checked_add(initial_balance)
+            .ok_or(TxError::SupplyOverflow)?;
         let wallet = {
             let mut history = self.wallet_history_mut(key);
             history.push(*transaction);
@@ -54,23 +230,122 @@ impl<'a> Schema<&'a mut Fork> {
             Wallet::new(
                 key,
                 name,
-                100,
+                initial_balance,
+                vec![],
+                vec![],
+                vec![],
                 vec![],
                 vec![],
                 vec![],
+                SpendingLimit::default(),
+                vec![],
                 history.len(),
                 &history_hash,
             )
         };
 
         println!("Creating a wallet {:?}", wallet);
+        if wallet.balance > 0 {
+            let change = BalanceChange {
+                wallet: *key,
+                asset_id: NATIVE_ASSET_ID.to_owned(),
+                before: 0,
+                after: wallet.balance,
+            };
+            self.update_receipt(transaction, |receipt| receipt.balance_changes.push(change));
+            self.total_supply_mut().set(total_supply);
+        }
         self.wallets_mut().put(key, wallet);
+        Ok(())
+    }
+
+    /// Creates an empty wallet for the fees treasury at genesis.
+    pub fn create_treasury(&mut self, key: &PublicKey) {
+        if self.wallet(key).is_none() {
+            let wallet = Wallet::new(
+                key,
+                "Treasury",
+                0,
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                SpendingLimit::default(),
+                vec![],
+                0,
+                &Hash::zero(),
+            );
+            self.wallets_mut().put(key, wallet);
+        }
+    }
+
+    /// Moves the fee selected by `fee` from the payer wallet to the treasury
+    /// and adds it to the collected fees totals.
+    pub fn charge_fee<F>(
+        &mut self,
+        payer: &PublicKey,
+        fee: F,
+        transaction: &Hash,
+    ) -> Result<(), TxError>
+    where
+        F: Fn(&mut TransactionFees) -> &mut u64,
+    {
+        let config = self.config();
+        let amount = *fee(&mut config.fees.clone());
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let payer_wallet = match self.wallet(payer) {
+            Some(val) => val,
+            None => return Err(TxError::WalletNotFound),
+        };
+        if payer_wallet.balance < amount {
+            return Err(TxError::InsufficientCurrencyAmount);
+        }
+        self.decrease_wallet_balance(&payer_wallet, NATIVE_ASSET_ID, amount, transaction)?;
+
+        // Treasury wallet is loaded after the charge, as the payer may be the treasury itself
+        let treasury = match self.wallet(&config.treasury) {
+            Some(val) => val,
+            None => return Err(TxError::WalletNotFound),
+        };
+        self.increase_wallet_balance(&treasury, NATIVE_ASSET_ID, amount, transaction)?;
+
+        // Fees recirculate through the treasury, so the totals grow without bound and saturate
+        let mut collected_fees = self.collected_fees();
+        let collected = fee(&mut collected_fees);
+        *collected = collected.saturating_add(amount);
+        self.collected_fees_mut().set(collected_fees);
+        Ok(())
     }
 
-    pub fn add_signer(
+    pub fn invite_signer(&mut self, wallet: &Wallet, signer: Signer, transaction: &Hash) -> Wallet {
+        let new_wallet = {
+            let mut history = self.wallet_history_mut(&wallet.pub_key);
+            history.push(*transaction);
+
+            let history_hash = history.merkle_root();
+
+            wallet.clone().invite_signer(signer.clone(), &history_hash)
+        };
+
+        println!(
+            "Inviting signer `{}` with weight {} to the wallet {}",
+            signer.pub_key.to_hex(),
+            signer.weight,
+            wallet.pub_key.to_hex()
+        );
+        self.wallets_mut().put(&wallet.pub_key, new_wallet.clone());
+        new_wallet
+    }
+
+    pub fn set_signer_group(
         &mut self,
         wallet: &Wallet,
-        signer: &PublicKey,
+        group: SignerGroup,
         transaction: &Hash,
     ) -> Wallet {
         let new_wallet = {
@@ -79,7 +354,34 @@ impl<'a> Schema<&'a mut Fork> {
 
             let history_hash = history.merkle_root();
 
-            wallet.clone().add_signer(signer, &history_hash)
+            wallet
+                .clone()
+                .set_signer_group(group.clone(), &history_hash)
+        };
+
+        println!(
+            "Setting signer group `{}` with threshold {} to the wallet {}",
+            group.name,
+            group.threshold,
+            wallet.pub_key.to_hex()
+        );
+        self.wallets_mut().put(&wallet.pub_key, new_wallet.clone());
+        new_wallet
+    }
+
+    pub fn accept_signer_invite(
+        &mut self,
+        wallet: &Wallet,
+        signer: &PublicKey,
+        transaction: &Hash,
+    ) -> Result<Wallet, TxError> {
+        let new_wallet = {
+            let mut history = self.wallet_history_mut(&wallet.pub_key);
+            history.push(*transaction);
+
+            let history_hash = history.merkle_root();
+
+            wallet.clone().accept_invite(signer, &history_hash)?
         };
 
         println!(
@@ -88,36 +390,70 @@ impl<'a> Schema<&'a mut Fork> {
             wallet.pub_key.to_hex()
         );
         self.wallets_mut().put(&wallet.pub_key, new_wallet.clone());
-        new_wallet
+        Ok(new_wallet)
     }
 
     pub fn add_pending_tx(
         &mut self,
         wallet: &Wallet,
-        tx_hash: &Hash,
-        recipient: &PublicKey,
-        amount: u64,
+        pending_tx: PendingTransaction,
         transaction: &Hash,
     ) -> Wallet {
+        println!(
+            "Creating pending transaction of {} coins transfer between wallets {} => {}",
+            pending_tx.amount,
+            wallet.pub_key.to_hex(),
+            pending_tx.recipient.to_hex()
+        );
+
         let new_wallet = {
             let mut history = self.wallet_history_mut(&wallet.pub_key);
             history.push(*transaction);
 
             let history_hash = history.merkle_root();
 
-            let pending_tx = PendingTransaction::new(tx_hash, recipient, amount);
-
             wallet.clone().add_pending_tx(pending_tx, &history_hash)
         };
 
+        self.wallets_mut().put(&wallet.pub_key, new_wallet.clone());
+        new_wallet
+    }
+
+    /// Removes the pending transaction which failed with `error` when it was confirmed.
+    pub fn reject_pending_tx(
+        &mut self,
+        wallet: &Wallet,
+        tx_hash: &Hash,
+        error: TxError,
+        transaction: &Hash,
+    ) -> Result<Wallet, TxError> {
         println!(
-            "Creating pending transaction of {} coins transfer between wallets {} => {}",
-            amount,
+            "Rejecting pending transaction {} of the wallet {}: {}",
+            tx_hash.to_hex(),
             wallet.pub_key.to_hex(),
-            recipient.to_hex()
+            error
         );
+
+        let new_wallet = {
+            let mut history = self.wallet_history_mut(&wallet.pub_key);
+            history.push(*transaction);
+
+            let history_hash = history.merkle_root();
+
+            wallet.clone().reject_pending_tx(tx_hash, &history_hash)?
+        };
+
+        let description = error.to_string();
+        let rejection = Rejection {
+            wallet: wallet.pub_key,
+            tx_hash: *tx_hash,
+            code: u32::from(error as u8),
+            description,
+        };
+        self.update_receipt(transaction, |receipt| receipt.rejections.push(rejection));
+
         self.wallets_mut().put(&wallet.pub_key, new_wallet.clone());
-        new_wallet
+        Ok(new_wallet)
     }
 
     pub fn confirm_pending_tx(
@@ -126,7 +462,7 @@ impl<'a> Schema<&'a mut Fork> {
         tx: &PendingTransaction,
         confirmation_block: u64,
         transaction: &Hash,
-    ) -> Wallet {
+    ) -> Result<Wallet, TxError> {
         let new_wallet = {
             let mut history = self.wallet_history_mut(&wallet.pub_key);
             history.push(*transaction);
@@ -135,21 +471,24 @@ impl<'a> Schema<&'a mut Fork> {
 
             let approved_tx = ApprovedTransaction {
                 tx_hash: tx.tx_hash,
+                kind: tx.kind,
                 recipient: tx.recipient,
+                asset_id: tx.asset_id.clone(),
                 amount: tx.amount,
+                period: tx.period,
+                payments: tx.payments.clone(),
                 approvals: tx.approvals.clone(),
                 confirmation_block,
             };
 
             wallet
                 .clone()
-                .remove_pending_tx(&tx.tx_hash, &history_hash)
-                .add_approved_tx(approved_tx, &history_hash)
+                .confirm_pending_tx(approved_tx, &history_hash)?
         };
 
         self.wallets_mut()
             .put(&new_wallet.pub_key, new_wallet.clone());
-        new_wallet
+        Ok(new_wallet)
     }
 
     pub fn sign_pending_tx(
@@ -158,7 +497,7 @@ impl<'a> Schema<&'a mut Fork> {
         tx_hash: &Hash,
         signer: &PublicKey,
         transaction: &Hash,
-    ) -> Wallet {
+    ) -> Result<Wallet, TxError> {
         let new_wallet = {
             let mut history = self.wallet_history_mut(&wallet.pub_key);
             history.push(*transaction);
@@ -167,66 +506,288 @@ impl<'a> Schema<&'a mut Fork> {
 
             wallet
                 .clone()
-                .sign_pending_tx(tx_hash, &signer, &history_hash)
+                .sign_pending_tx(tx_hash, &signer, &history_hash)?
+        };
+
+        let approval = Approval {
+            wallet: wallet.pub_key,
+            tx_hash: *tx_hash,
+            signer: *signer,
         };
+        self.update_receipt(transaction, |receipt| receipt.approvals.push(approval));
 
         self.wallets_mut()
             .put(&new_wallet.pub_key, new_wallet.clone());
-        new_wallet
+        Ok(new_wallet)
+    }
+
+    pub fn revoke_approval(
+        &mut self,
+        wallet: &Wallet,
+        tx_hash: &Hash,
+        signer: &PublicKey,
+        transaction: &Hash,
+    ) -> Result<Wallet, TxError> {
+        let new_wallet = {
+            let mut history = self.wallet_history_mut(&wallet.pub_key);
+            history.push(*transaction);
+
+            let history_hash = history.merkle_root();
+
+            wallet
+                .clone()
+                .revoke_approval(tx_hash, signer, &history_hash)?
+        };
+
+        let approval = Approval {
+            wallet: wallet.pub_key,
+            tx_hash: *tx_hash,
+            signer: *signer,
+        };
+        self.update_receipt(transaction, |receipt| {
+            receipt.revoked_approvals.push(approval)
+        });
+
+        self.wallets_mut()
+            .put(&new_wallet.pub_key, new_wallet.clone());
+        Ok(new_wallet)
     }
 
     pub fn increase_wallet_balance(
         &mut self,
         wallet: &Wallet,
+        asset_id: &str,
         amount: u64,
         transaction: &Hash,
-    ) -> Wallet {
+    ) -> Result<Wallet, TxError> {
+        let balance = wallet
+            .balance_of(asset_id)
+            .checked_add(amount)
+            .ok_or(TxError::BalanceOverflow)?;
         let new_wallet = {
             let mut history = self.wallet_history_mut(&wallet.pub_key);
             history.push(*transaction);
 
             let history_hash = history.merkle_root();
 
-            let balance = wallet.balance;
-            wallet.clone().set_balance(balance + amount, &history_hash)
+            wallet.clone().set_balance(asset_id, balance, &history_hash)
         };
 
+        self.record_balance_change(
+            wallet,
+            asset_id,
+            new_wallet.balance_of(asset_id),
+            transaction,
+        );
+
         self.wallets_mut()
             .put(&new_wallet.pub_key, new_wallet.clone());
-        new_wallet
+        Ok(new_wallet)
     }
 
     pub fn decrease_wallet_balance(
         &mut self,
         wallet: &Wallet,
+        asset_id: &str,
         amount: u64,
         transaction: &Hash,
-    ) -> Wallet {
+    ) -> Result<Wallet, TxError> {
+        let balance = wallet
+            .balance_of(asset_id)
+            .checked_sub(amount)
+            .ok_or(TxError::InsufficientCurrencyAmount)?;
         let new_wallet = {
             let mut history = self.wallet_history_mut(&wallet.pub_key);
             history.push(*transaction);
 
             let history_hash = history.merkle_root();
 
-            let balance = wallet.balance;
-            wallet.clone().set_balance(balance - amount, &history_hash)
+            wallet.clone().set_balance(asset_id, balance, &history_hash)
         };
 
+        self.record_balance_change(
+            wallet,
+            asset_id,
+            new_wallet.balance_of(asset_id),
+            transaction,
+        );
+
         self.wallets_mut().put(&wallet.pub_key, new_wallet.clone());
+        Ok(new_wallet)
+    }
+
+    pub fn issue_asset(
+        &mut self,
+        wallet: &Wallet,
+        asset_id: &str,
+        name: &str,
+        amount: u64,
+        transaction: &Hash,
+    ) -> Result<Wallet, TxError> {
+        let asset = match self.asset(asset_id) {
+            Some(asset) => {
+                let total_supply = asset
+                    .total_supply
+                    .checked_add(amount)
+                    .ok_or(TxError::SupplyOverflow)?;
+                asset.set_total_supply(total_supply)
+            }
+            None => Asset::new(asset_id, name, &wallet.pub_key, amount),
+        };
+
+        println!(
+            "Issuing {} of asset `{}` to the wallet {}",
+            amount,
+            asset_id,
+            wallet.pub_key.to_hex()
+        );
+        self.assets_mut().put(&Asset::key(asset_id), asset);
+        self.increase_wallet_balance(wallet, asset_id, amount, transaction)
+    }
+
+    pub fn set_spending_limit(
+        &mut self,
+        wallet: &Wallet,
+        spending_limit: SpendingLimit,
+        transaction: &Hash,
+    ) -> Wallet {
+        let new_wallet = {
+            let mut history = self.wallet_history_mut(&wallet.pub_key);
+            history.push(*transaction);
+
+            let history_hash = history.merkle_root();
+
+            wallet
+                .clone()
+                .set_spending_limit(spending_limit, &history_hash)
+        };
+
+        self.wallets_mut()
+            .put(&new_wallet.pub_key, new_wallet.clone());
         new_wallet
     }
 
+    pub fn add_spending(&mut self, wallet: &Wallet, amount: u64, transaction: &Hash) -> Wallet {
+        let spending = Spending {
+            height: self.current_height(),
+            amount,
+        };
+        let new_wallet = {
+            let mut history = self.wallet_history_mut(&wallet.pub_key);
+            history.push(*transaction);
+
+            let history_hash = history.merkle_root();
+
+            wallet.clone().add_spending(spending, &history_hash)
+        };
+
+        self.wallets_mut()
+            .put(&new_wallet.pub_key, new_wallet.clone());
+        new_wallet
+    }
+
+    pub fn mint(
+        &mut self,
+        wallet: &Wallet,
+        amount: u64,
+        transaction: &Hash,
+    ) -> Result<Wallet, TxError> {
+        let total_supply = self
+            .total_supply()
+            .checked_add(amount)
+            .ok_or(TxError::SupplyOverflow)?;
+        self.total_supply_mut().set(total_supply);
+
+        println!(
+            "Minting {} coins to the wallet {}",
+            amount,
+            wallet.pub_key.to_hex()
+        );
+        self.increase_wallet_balance(wallet, NATIVE_ASSET_ID, amount, transaction)
+    }
+
+    pub fn burn(
+        &mut self,
+        wallet: &Wallet,
+        amount: u64,
+        transaction: &Hash,
+    ) -> Result<Wallet, TxError> {
+        let total_supply = self
+            .total_supply()
+            .checked_sub(amount)
+            .ok_or(TxError::InsufficientCurrencyAmount)?;
+        self.total_supply_mut().set(total_supply);
+
+        println!(
+            "Burning {} coins from the wallet {}",
+            amount,
+            wallet.pub_key.to_hex()
+        );
+        self.decrease_wallet_balance(wallet, NATIVE_ASSET_ID, amount, transaction)
+    }
+
     pub fn add_awaiting_tx(&mut self, service_hash: &Hash, origin_hash: &Hash, sender: &PublicKey) {
         self.awaiting_txs_mut().put(
-            &service_hash,
+            &origin_hash,
             SignTx {
                 origin: sender.clone(),
                 tx_hash: origin_hash.clone(),
+                seed: 0,
             },
         );
+
+        self.update_receipt(service_hash, |receipt| {
+            receipt.thresholds_reached.push(*origin_hash);
+            receipt.awaiting_created.push(*origin_hash);
+        });
+    }
+
+    pub fn add_incoming_tx(&mut self, recipient: &PublicKey, tx: IncomingTransaction) {
+        self.incoming_txs_mut(recipient).push(tx);
+    }
+
+    pub fn remove_awaiting_tx(&mut self, hash: &Hash, transaction: &Hash) {
+        if self.awaiting_txs_mut().contains(hash) {
+            self.awaiting_txs_mut().remove(&hash);
+            self.update_receipt(transaction, |receipt| receipt.awaiting_consumed.push(*hash));
+        }
+    }
+
+    pub fn add_config_proposal(&mut self, proposal_hash: &Hash, proposal: ConfigProposal) {
+        println!(
+            "Proposing service config {:?} from height {}",
+            proposal.config, proposal.activation_height
+        );
+        self.config_proposals_mut().put(proposal_hash, proposal);
+    }
+
+    pub fn vote_config_proposal(
+        &mut self,
+        proposal_hash: &Hash,
+        proposal: &ConfigProposal,
+        validator: &PublicKey,
+    ) {
+        let proposal = proposal.clone().add_vote(validator);
+        self.config_proposals_mut().put(proposal_hash, proposal);
     }
 
-    pub fn remove_awaiting_tx(&mut self, hash: &Hash) {
-        self.awaiting_txs_mut().remove(&hash);
+    /// Activates proposals accepted by the validators supermajority which take effect
+    /// from the next block, and drops the ones which didn't get enough votes in time.
+    pub fn activate_config_proposals(&mut self) {
+        let majority = State::byzantine_majority_count(self.validators().len());
+        let next_height = self.current_height() + 1;
+        let due: Vec<(Hash, ConfigProposal)> = self
+            .config_proposals()
+            .iter()
+            .filter(|(_, proposal)| proposal.activation_height <= next_height)
+            .collect();
+
+        for (proposal_hash, proposal) in due {
+            if proposal.votes.len() >= majority {
+                println!("Activating service config {:?}", proposal.config);
+                self.config_mut().set(proposal.config);
+            }
+            self.config_proposals_mut().remove(&proposal_hash);
+        }
     }
 }
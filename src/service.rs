@@ -1,4 +1,6 @@
 use super::api::Api;
+use super::config::ServiceConfig;
+use super::events::EventHub;
 use super::schema::Schema as AppSchema;
 use super::transaction::{ConfirmationTx, WalletTransaction};
 use exonum::api::ServiceApiBuilder;
@@ -7,26 +9,55 @@ use exonum::blockchain::{
 };
 use exonum::crypto::Hash;
 use exonum::messages::RawTransaction;
-use exonum::storage::Snapshot;
+use exonum::storage::{Fork, Snapshot};
 use failure::Error;
+use serde_json::Value;
 
-pub struct Service;
+pub const SERVICE_ID: u16 = 1;
+
+pub struct Service {
+    name: String,
+    config: ServiceConfig,
+    events: EventHub,
+}
+
+impl Service {
+    pub fn new(config: ServiceConfig) -> Self {
+        Self {
+            name: config.service_name.clone(),
+            config,
+            events: EventHub::default(),
+        }
+    }
+}
 
 impl blockchain::Service for Service {
-    fn service_name(&self) -> &'static str {
-        "cryptocurrency"
+    fn service_name(&self) -> &str {
+        &self.name
     }
 
     fn service_id(&self) -> u16 {
-        1
+        SERVICE_ID
     }
 
     fn tx_from_raw(&self, raw: RawTransaction) -> Result<Box<dyn Transaction>, Error> {
         WalletTransaction::tx_from_raw(raw).map(Into::into)
     }
 
-    fn state_hash(&self, _: &Snapshot) -> Vec<Hash> {
-        vec![]
+    fn state_hash(&self, snapshot: &Snapshot) -> Vec<Hash> {
+        AppSchema::new(snapshot).state_hash()
+    }
+
+    fn initialize(&self, fork: &mut Fork) -> Value {
+        let mut schema = AppSchema::new(fork);
+        schema.config_mut().set(self.config.clone());
+        schema.create_treasury(&self.config.treasury);
+
+        serde_json::to_value(&self.config).expect("Couldn't serialize service config")
+    }
+
+    fn before_commit(&self, fork: &mut Fork) {
+        AppSchema::new(fork).activate_config_proposals();
     }
 
     fn after_commit(&self, context: &ServiceContext) {
@@ -35,18 +66,25 @@ impl blockchain::Service for Service {
         let txs = blockchain_schema.block_transactions(context.height());
         let awaiting_txs = app_schema.awaiting_txs();
 
-        txs.iter().for_each(|tx_hash| {
-            if let Some(tx) = awaiting_txs.get(&tx_hash) {
-                context.broadcast_transaction(ConfirmationTx {
-                    tx_hash: tx.tx_hash,
-                    sender: tx.origin,
-                    confirmation_block: context.height().0,
-                });
-            }
-        });
+        // Receipts tell which pending transactions started awaiting confirmation in the block
+        txs.iter()
+            .filter_map(|tx_hash| app_schema.receipt(&tx_hash))
+            .flat_map(|receipt| receipt.awaiting_created)
+            .for_each(|pending_hash| {
+                if let Some(tx) = awaiting_txs.get(&pending_hash) {
+                    context.broadcast_transaction(ConfirmationTx {
+                        tx_hash: tx.tx_hash,
+                        sender: tx.origin,
+                        confirmation_block: context.height().0,
+                    });
+                }
+            });
+
+        self.events.notify(context.snapshot(), context.height());
     }
 
     fn wire_api(&self, builder: &mut ServiceApiBuilder) {
         Api::wire(builder);
+        self.events.wire(builder);
     }
 }
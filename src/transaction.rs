@@ -1,41 +1,212 @@
+use super::asset::NATIVE_ASSET_ID;
+use super::config::{ConfigProposal, ServiceConfig};
 use super::proto;
 use super::schema::Schema;
+use super::wallet::{find_signer, Signer, SignerGroup, SpendingLimit, Wallet};
 use exonum::blockchain::{ExecutionError, ExecutionResult, Transaction, TransactionContext};
 use exonum::crypto::{Hash, PublicKey};
+use exonum::proto::ProtobufConvert;
+use exonum::storage::Snapshot;
 use exonum_derive::ProtobufConvert;
-use failure::Fail;
+use failure::{Error, Fail};
 use serde_derive::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, ProtobufConvert, Deserialize, Serialize)]
+/// Operation performed once a pending transaction is confirmed.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingKind {
+    Transfer,
+    Mint,
+    Burn,
+    SetSpendingLimit,
+    BatchTransfer,
+}
+
+impl ProtobufConvert for PendingKind {
+    type ProtoStruct = proto::PendingKind;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        match self {
+            PendingKind::Transfer => proto::PendingKind::TRANSFER,
+            PendingKind::Mint => proto::PendingKind::MINT,
+            PendingKind::Burn => proto::PendingKind::BURN,
+            PendingKind::SetSpendingLimit => proto::PendingKind::SET_SPENDING_LIMIT,
+            PendingKind::BatchTransfer => proto::PendingKind::BATCH_TRANSFER,
+        }
+    }
+
+    fn from_pb(pb: Self::ProtoStruct) -> Result<Self, Error> {
+        Ok(match pb {
+            proto::PendingKind::TRANSFER => PendingKind::Transfer,
+            proto::PendingKind::MINT => PendingKind::Mint,
+            proto::PendingKind::BURN => PendingKind::Burn,
+            proto::PendingKind::SET_SPENDING_LIMIT => PendingKind::SetSpendingLimit,
+            proto::PendingKind::BATCH_TRANSFER => PendingKind::BatchTransfer,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Deserialize, Serialize)]
+#[exonum(pb = "proto::Payment")]
+pub struct Payment {
+    pub recipient: PublicKey,
+    pub amount: u64,
+}
+
+/// Approval progress of a wallet signer group, frozen in a pending transaction.
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Deserialize, Serialize)]
+#[exonum(pb = "proto::GroupQuorum")]
+pub struct GroupQuorum {
+    pub name: String,
+    pub members: Vec<PublicKey>,
+    pub threshold: u64,
+    /// Weight of the given approvals of the group members.
+    pub approved_weight: u64,
+}
+
+impl GroupQuorum {
+    pub fn is_met(&self) -> bool {
+        self.approved_weight >= self.threshold
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Deserialize, Serialize)]
 #[exonum(pb = "proto::PendingTransaction")]
 pub struct PendingTransaction {
     pub tx_hash: Hash,
+    pub kind: PendingKind,
     pub recipient: PublicKey,
+    pub asset_id: String,
     pub amount: u64,
+    /// Period of the spending limit set by `SetSpendingLimit` transaction.
+    pub period: u64,
+    /// Payments of `BatchTransfer` transaction, `amount` holds their total.
+    pub payments: Vec<Payment>,
     pub approvals: Vec<PublicKey>,
+    /// Wallet signers allowed to approve the transaction, frozen when it is created.
+    pub signers: Vec<Signer>,
+    /// Approval weight required to execute the transaction.
+    pub required_weight: u64,
+    /// Weight of the given approvals, the initiator approval weighs 1.
+    pub approved_weight: u64,
+    /// Wallet signer groups, each of them has to reach its own threshold.
+    pub groups: Vec<GroupQuorum>,
+    /// Transfer fits in the spending limit, the spending is recorded once it is applied.
+    pub within_limit: bool,
 }
 
 impl PendingTransaction {
-    pub fn new(&tx_hash: &Hash, &recipient: &PublicKey, amount: u64) -> Self {
+    pub fn new(
+        &tx_hash: &Hash,
+        kind: PendingKind,
+        &recipient: &PublicKey,
+        asset_id: &str,
+        amount: u64,
+    ) -> Self {
         Self {
             tx_hash,
+            kind,
             recipient,
+            asset_id: asset_id.to_owned(),
             amount,
+            period: 0,
+            payments: vec![],
             approvals: vec![],
+            signers: vec![],
+            required_weight: 0,
+            approved_weight: 1,
+            groups: vec![],
+            within_limit: false,
+        }
+    }
+
+    /// Freezes the signers allowed to approve the transaction and the approval weight it requires.
+    pub fn with_signers(self, signers: &[Signer], required_weight: u64) -> Self {
+        Self {
+            signers: signers.to_vec(),
+            required_weight,
+            ..self
         }
     }
+
+    /// Freezes the signer groups which have to approve the transaction.
+    pub fn with_groups(self, groups: &[SignerGroup]) -> Self {
+        let groups = groups
+            .iter()
+            .map(|group| GroupQuorum {
+                name: group.name.clone(),
+                members: group.members.clone(),
+                threshold: group.threshold,
+                approved_weight: 0,
+            })
+            .collect();
+        Self { groups, ..self }
+    }
+
+    /// Adds the signer approval, its weight counts toward the transaction and its groups.
+    pub fn approve(&mut self, signer: &PublicKey) -> Result<(), TxError> {
+        let weight = find_signer(&self.signers, signer)
+            .ok_or(TxError::UnauthorizedSigner)?
+            .weight;
+        self.approvals.push(*signer);
+        self.approved_weight = self.approved_weight.saturating_add(weight);
+        for group in &mut self.groups {
+            if group.members.contains(signer) {
+                group.approved_weight = group.approved_weight.saturating_add(weight);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the signer approval along with its weight.
+    pub fn revoke(&mut self, signer: &PublicKey) -> Result<(), TxError> {
+        let index = self
+            .approvals
+            .iter()
+            .position(|item| item == signer)
+            .ok_or(TxError::ApprovalNotFound)?;
+        let weight = find_signer(&self.signers, signer).map_or(0, |item| item.weight);
+        self.approvals.remove(index);
+        self.approved_weight = self.approved_weight.saturating_sub(weight);
+        for group in &mut self.groups {
+            if group.members.contains(signer) {
+                group.approved_weight = group.approved_weight.saturating_sub(weight);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the transaction has enough approval weight to be executed
+    /// and the quorums of all its groups are met.
+    pub fn is_approved(&self) -> bool {
+        self.approved_weight >= self.required_weight && self.groups.iter().all(GroupQuorum::is_met)
+    }
 }
 
-#[derive(Clone, Debug, ProtobufConvert, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Deserialize, Serialize)]
 #[exonum(pb = "proto::ApprovedTransaction")]
 pub struct ApprovedTransaction {
     pub tx_hash: Hash,
+    pub kind: PendingKind,
     pub recipient: PublicKey,
+    pub asset_id: String,
     pub amount: u64,
+    pub period: u64,
+    pub payments: Vec<Payment>,
     pub approvals: Vec<PublicKey>,
     pub confirmation_block: u64,
 }
 
+#[derive(Clone, Debug, ProtobufConvert, Deserialize, Serialize)]
+#[exonum(pb = "proto::IncomingTransaction")]
+pub struct IncomingTransaction {
+    pub tx_hash: Hash,
+    pub sender: PublicKey,
+    pub asset_id: String,
+    pub amount: u64,
+    pub confirmation_block: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, TransactionSet)]
 pub enum WalletTransaction {
     CreateWallet(CreateWalletTx),
@@ -43,6 +214,61 @@ pub enum WalletTransaction {
     Transfer(TransferTx),
     Sign(SignTx),
     Confirmation(ConfirmationTx),
+    IssueAsset(IssueAssetTx),
+    Mint(MintTx),
+    Burn(BurnTx),
+    SetSpendingLimit(SetSpendingLimitTx),
+    BatchTransfer(BatchTransferTx),
+    ProposeServiceConfig(ProposeServiceConfigTx),
+    VoteServiceConfig(VoteServiceConfigTx),
+    AcceptSignerInvite(AcceptSignerInviteTx),
+    SetSignerGroup(SetSignerGroupTx),
+    RevokeApproval(RevokeApprovalTx),
+}
+
+impl WalletTransaction {
+    /// Returns keys of the wallets which can be changed by the transaction.
+    ///
+    /// The author and the treasury are always included, as the author pays the fee.
+    pub fn affected_wallets<T>(&self, author: &PublicKey, schema: &Schema<T>) -> Vec<PublicKey>
+    where
+        T: AsRef<Snapshot>,
+    {
+        let mut keys = vec![*author, schema.config().treasury];
+        match self {
+            WalletTransaction::CreateWallet(_)
+            | WalletTransaction::AddSigner(_)
+            | WalletTransaction::Transfer(_)
+            | WalletTransaction::IssueAsset(_)
+            | WalletTransaction::Mint(_)
+            | WalletTransaction::Burn(_)
+            | WalletTransaction::SetSpendingLimit(_)
+            | WalletTransaction::BatchTransfer(_)
+            | WalletTransaction::SetSignerGroup(_)
+            | WalletTransaction::ProposeServiceConfig(_)
+            | WalletTransaction::VoteServiceConfig(_) => {}
+            WalletTransaction::Sign(tx) => keys.push(tx.origin),
+            WalletTransaction::RevokeApproval(tx) => keys.push(tx.origin),
+            WalletTransaction::AcceptSignerInvite(tx) => keys.push(tx.wallet),
+            WalletTransaction::Confirmation(tx) => {
+                let pending_tx = schema.wallet(&tx.sender).and_then(|wallet| {
+                    wallet
+                        .pending_txs
+                        .into_iter()
+                        .find(|item| item.tx_hash == tx.tx_hash)
+                });
+
+                keys.push(tx.sender);
+                if let Some(pending_tx) = pending_tx {
+                    keys.push(pending_tx.recipient);
+                    keys.extend(pending_tx.payments.iter().map(|item| item.recipient));
+                }
+            }
+        }
+        keys.sort();
+        keys.dedup();
+        keys
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
@@ -56,12 +282,14 @@ impl Transaction for CreateWalletTx {
         let pub_key = &context.author();
         let hash = context.tx_hash();
         let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
 
         if schema.wallet(pub_key).is_some() {
             Err(TxError::WalletAlreadyExists)?
         }
 
-        schema.create_wallet(&pub_key, &self.name, &hash);
+        schema.create_wallet(&pub_key, &self.name, &hash)?;
+        schema.charge_fee(pub_key, |fees| &mut fees.create_wallet, &hash)?;
         Ok(())
     }
 }
@@ -70,6 +298,7 @@ impl Transaction for CreateWalletTx {
 #[exonum(pb = "proto::AddSignerTx")]
 pub struct AddSignerTx {
     pub signer: PublicKey,
+    pub weight: u64,
 }
 
 impl Transaction for AddSignerTx {
@@ -77,13 +306,119 @@ impl Transaction for AddSignerTx {
         let pub_key = &context.author();
         let hash = context.tx_hash();
         let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
 
         let wallet = match schema.wallet(pub_key) {
             Some(val) => val,
             None => Err(TxError::WalletNotFound)?,
         };
 
-        schema.add_signer(&wallet, &self.signer, &hash);
+        if self.weight == 0 {
+            Err(TxError::ZeroWeight)?;
+        }
+
+        if wallet.is_signer(&self.signer) {
+            Err(TxError::AlreadySigner)?;
+        }
+
+        if wallet.is_invited(&self.signer) {
+            Err(TxError::AlreadyInvited)?;
+        }
+
+        // Pending invites are counted, as each of them may be accepted
+        let signers = wallet.signers.len() + wallet.invites.len();
+        if signers as u64 >= schema.config().max_signers {
+            Err(TxError::TooManySigners)?;
+        }
+
+        schema.invite_signer(&wallet, Signer::new(&self.signer, self.weight), &hash);
+        schema.charge_fee(pub_key, |fees| &mut fees.add_signer, &hash)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::AcceptSignerInviteTx")]
+pub struct AcceptSignerInviteTx {
+    pub wallet: PublicKey,
+}
+
+impl Transaction for AcceptSignerInviteTx {
+    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+        let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
+
+        let wallet = match schema.wallet(&self.wallet) {
+            Some(val) => val,
+            None => Err(TxError::WalletNotFound)?,
+        };
+
+        if !wallet.is_invited(pub_key) {
+            Err(TxError::InviteNotFound)?;
+        }
+
+        // Limit may be lowered by the config change after the invite
+        if wallet.signers.len() as u64 >= schema.config().max_signers {
+            Err(TxError::TooManySigners)?;
+        }
+
+        schema.accept_signer_invite(&wallet, pub_key, &hash)?;
+        // Invited key may have no wallet, so the inviting wallet pays the fee
+        schema.charge_fee(&self.wallet, |fees| &mut fees.accept_signer_invite, &hash)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::SetSignerGroupTx")]
+pub struct SetSignerGroupTx {
+    pub name: String,
+    pub members: Vec<PublicKey>,
+    /// Zero threshold removes the group.
+    pub threshold: u64,
+}
+
+impl Transaction for SetSignerGroupTx {
+    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+        let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
+
+        let wallet = match schema.wallet(pub_key) {
+            Some(val) => val,
+            None => Err(TxError::WalletNotFound)?,
+        };
+
+        let group = SignerGroup {
+            name: self.name.clone(),
+            members: self.members.clone(),
+            threshold: self.threshold,
+        };
+        let exists = wallet.groups.iter().any(|item| item.name == self.name);
+
+        if self.threshold == 0 {
+            if !exists {
+                Err(TxError::GroupNotFound)?;
+            }
+        } else {
+            if !self.members.iter().all(|key| wallet.is_signer(key)) {
+                Err(TxError::GroupMemberNotFound)?;
+            }
+
+            if group.members_weight(&wallet.signers) < self.threshold {
+                Err(TxError::UnreachableGroupThreshold)?;
+            }
+
+            if !exists && wallet.groups.len() as u64 >= schema.config().max_groups {
+                Err(TxError::TooManyGroups)?;
+            }
+        }
+
+        schema.set_signer_group(&wallet, group, &hash);
+        schema.charge_fee(pub_key, |fees| &mut fees.set_signer_group, &hash)?;
         Ok(())
     }
 }
@@ -92,6 +427,7 @@ impl Transaction for AddSignerTx {
 #[exonum(pb = "proto::TransferTx")]
 pub struct TransferTx {
     pub recipient: PublicKey,
+    pub asset_id: String,
     pub amount: u64,
     pub seed: u64,
 }
@@ -101,27 +437,79 @@ impl Transaction for TransferTx {
         let pub_key = &context.author();
         let hash = context.tx_hash();
         let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
 
         let sender_wallet = match schema.wallet(pub_key) {
             Some(val) => val,
             None => Err(TxError::SenderNotFound)?,
         };
 
+        if self.recipient == *pub_key {
+            Err(TxError::SelfTransfer)?;
+        }
+
+        if schema.wallet(&self.recipient).is_none() {
+            Err(TxError::RecipientNotFound)?;
+        }
+
+        if self.amount == 0 {
+            Err(TxError::ZeroAmount)?;
+        }
+
+        if self.asset_id != NATIVE_ASSET_ID && schema.asset(&self.asset_id).is_none() {
+            Err(TxError::AssetNotFound)?;
+        }
+
         let amount = self.amount;
 
-        // Check if balance is higher than desired transfer amount
-        if sender_wallet.balance < amount {
+        // Check if balance is higher than desired transfer amount and the fee charged below
+        let fee = schema.config().fees.transfer;
+        if !sender_wallet.can_spend(&self.asset_id, amount, fee) {
             Err(TxError::InsufficientCurrencyAmount)?;
         }
 
+        if sender_wallet.pending_txs.len() as u64 >= schema.config().max_pending_txs {
+            Err(TxError::TooManyPendingTransactions)?;
+        }
+
+        // Native coins transfer within the spending limit doesn't need signers approval
+        let limit = &sender_wallet.spending_limit;
+        let within_limit = self.asset_id == NATIVE_ASSET_ID
+            && limit.amount > 0
+            && sender_wallet
+                .spent_before(schema.current_height())
+                .and_then(|spent| spent.checked_add(amount))
+                .map_or(false, |spent| spent <= limit.amount);
+
         // Check if wallet has trusted signers assigned, and create pending transaction if truthy
         // Immediately executes transfer in the other case
-        schema.add_pending_tx(&sender_wallet, &hash, &self.recipient, amount, &hash);
+        let (required_weight, groups) = if within_limit {
+            (0, vec![])
+        } else {
+            (
+                schema.required_weight(&sender_wallet),
+                sender_wallet.groups.clone(),
+            )
+        };
+        let pending_tx = PendingTransaction {
+            within_limit,
+            ..PendingTransaction::new(
+                &hash,
+                PendingKind::Transfer,
+                &self.recipient,
+                &self.asset_id,
+                amount,
+            )
+            .with_signers(&sender_wallet.signers, required_weight)
+            .with_groups(&groups)
+        };
+        let sender_wallet = schema.add_pending_tx(&sender_wallet, pending_tx, &hash);
 
-        if sender_wallet.signers.len() == 0 {
-            schema.add_awaiting_tx(&hash, &hash, &pub_key);
+        if sender_wallet.signers.len() == 0 || within_limit {
+            schema.add_awaiting_tx(&hash, &hash, pub_key);
         }
 
+        schema.charge_fee(pub_key, |fees| &mut fees.transfer, &hash)?;
         Ok(())
     }
 }
@@ -131,6 +519,8 @@ impl Transaction for TransferTx {
 pub struct SignTx {
     pub origin: PublicKey,
     pub tx_hash: Hash,
+    /// Tells apart approvals of the same signer, e.g. given again after a revoke.
+    pub seed: u64,
 }
 
 impl Transaction for SignTx {
@@ -138,6 +528,7 @@ impl Transaction for SignTx {
         let pub_key = &context.author();
         let hash = context.tx_hash();
         let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
 
         // Wallet, holding pending transactions
         let origin_wallet = match schema.wallet(&self.origin) {
@@ -157,8 +548,8 @@ impl Transaction for SignTx {
             None => Err(TxError::PendingTransactionNotFound)?,
         };
 
-        // Check if public key exist in origin wallet's `signers` vector
-        if !origin_wallet.signers.contains(&pub_key) {
+        // Check if public key was the wallet signer when the transaction was created
+        if find_signer(&transaction.signers, &pub_key).is_none() {
             Err(TxError::UnauthorizedSigner)?;
         }
 
@@ -167,16 +558,68 @@ impl Transaction for SignTx {
             Err(TxError::AlreadySigned)?;
         }
 
-        let signers_amount = origin_wallet.signers.len() as f64;
-        let signs_amount = transaction.approvals.len() as u64;
-
-        schema.sign_pending_tx(&origin_wallet, &tx_hash, &pub_key, &hash);
-        // Check if 2/3 majority achieved, and immediately execute transfer if truthy
-        // +2 means +1 for transaction initiator and +1 for current singature that isn't added yet
-        if signs_amount + 2 >= (2f64 * signers_amount / 3f64).floor() as u64 {
+        let origin_wallet = schema.sign_pending_tx(&origin_wallet, &tx_hash, &pub_key, &hash)?;
+        let approved = origin_wallet
+            .pending_txs
+            .iter()
+            .any(|item| item.tx_hash == tx_hash && item.is_approved());
+        // Check if configured majority and group quorums are achieved, and immediately
+        // execute transfer if truthy. Approvals given after that don't confirm it once more
+        if approved && !schema.awaiting_txs().contains(&tx_hash) {
             schema.add_awaiting_tx(&hash, &tx_hash, &self.origin);
         }
 
+        schema.charge_fee(pub_key, |fees| &mut fees.sign, &hash)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::RevokeApprovalTx")]
+pub struct RevokeApprovalTx {
+    pub origin: PublicKey,
+    pub tx_hash: Hash,
+}
+
+impl Transaction for RevokeApprovalTx {
+    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+        let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
+
+        let origin_wallet = match schema.wallet(&self.origin) {
+            Some(val) => val,
+            None => Err(TxError::SenderNotFound)?,
+        };
+
+        let tx_hash = self.tx_hash;
+
+        // Approval may be revoked only while the transaction is pending
+        let transaction = match origin_wallet
+            .pending_txs
+            .iter()
+            .find(|item| item.tx_hash == tx_hash)
+        {
+            Some(tx) => tx,
+            None => Err(TxError::PendingTransactionNotFound)?,
+        };
+
+        if !transaction.approvals.contains(pub_key) {
+            Err(TxError::ApprovalNotFound)?;
+        }
+
+        let origin_wallet = schema.revoke_approval(&origin_wallet, &tx_hash, pub_key, &hash)?;
+        let approved = origin_wallet
+            .pending_txs
+            .iter()
+            .any(|item| item.tx_hash == tx_hash && item.is_approved());
+        // Transaction isn't confirmed anymore if the threshold was reached by the approval
+        if !approved {
+            schema.remove_awaiting_tx(&tx_hash, &hash);
+        }
+
+        schema.charge_fee(pub_key, |fees| &mut fees.revoke_approval, &hash)?;
         Ok(())
     }
 }
@@ -189,10 +632,108 @@ pub struct ConfirmationTx {
     pub confirmation_block: u64,
 }
 
+impl ConfirmationTx {
+    /// Validates the approved transaction again, as the state may change while it was pending.
+    fn check_pending_tx<T>(
+        &self,
+        schema: &Schema<T>,
+        wallet: &Wallet,
+        transaction: &PendingTransaction,
+    ) -> Result<(), TxError>
+    where
+        T: AsRef<Snapshot>,
+    {
+        match transaction.kind {
+            PendingKind::Transfer => {
+                if transaction.recipient == self.sender {
+                    Err(TxError::SelfTransfer)?;
+                }
+                if transaction.amount == 0 {
+                    Err(TxError::ZeroAmount)?;
+                }
+                // Fee was charged when the transaction was proposed
+                if !wallet.can_spend(&transaction.asset_id, transaction.amount, 0) {
+                    Err(TxError::InsufficientCurrencyAmount)?;
+                }
+
+                let recipient_wallet = match schema.wallet(&transaction.recipient) {
+                    Some(val) => val,
+                    None => Err(TxError::RecipientNotFound)?,
+                };
+                if recipient_wallet
+                    .balance_of(&transaction.asset_id)
+                    .checked_add(transaction.amount)
+                    .is_none()
+                {
+                    Err(TxError::BalanceOverflow)?;
+                }
+            }
+            PendingKind::Mint => {
+                if schema
+                    .total_supply()
+                    .checked_add(transaction.amount)
+                    .is_none()
+                {
+                    Err(TxError::SupplyOverflow)?;
+                }
+            }
+            PendingKind::Burn => {
+                // Fee was charged when the transaction was proposed
+                if !wallet.can_spend(NATIVE_ASSET_ID, transaction.amount, 0) {
+                    Err(TxError::InsufficientCurrencyAmount)?;
+                }
+            }
+            PendingKind::BatchTransfer => {
+                if transaction
+                    .payments
+                    .iter()
+                    .any(|item| item.recipient == self.sender)
+                {
+                    Err(TxError::SelfTransfer)?;
+                }
+                if transaction.payments.iter().any(|item| item.amount == 0) {
+                    Err(TxError::ZeroAmount)?;
+                }
+                // Fee was charged when the transaction was proposed
+                if !wallet.can_spend(&transaction.asset_id, transaction.amount, 0) {
+                    Err(TxError::InsufficientCurrencyAmount)?;
+                }
+
+                // Recipient may be paid more than once, so its payments are summed up
+                let mut balances: Vec<(PublicKey, u64)> = Vec::new();
+                for payment in &transaction.payments {
+                    let index = match balances
+                        .iter()
+                        .position(|(recipient, _)| *recipient == payment.recipient)
+                    {
+                        Some(index) => index,
+                        None => {
+                            let recipient_wallet = match schema.wallet(&payment.recipient) {
+                                Some(val) => val,
+                                None => Err(TxError::RecipientNotFound)?,
+                            };
+                            let balance = recipient_wallet.balance_of(&transaction.asset_id);
+                            balances.push((payment.recipient, balance));
+                            balances.len() - 1
+                        }
+                    };
+                    balances[index].1 = balances[index]
+                        .1
+                        .checked_add(payment.amount)
+                        .ok_or(TxError::BalanceOverflow)?;
+                }
+            }
+            PendingKind::SetSpendingLimit => {}
+        }
+        Ok(())
+    }
+}
+
 impl Transaction for ConfirmationTx {
     fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
         let hash = context.tx_hash();
         let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
 
         let wallet = match schema.wallet(&self.sender) {
             Some(val) => val,
@@ -208,15 +749,480 @@ impl Transaction for ConfirmationTx {
             None => Err(TxError::PendingTransactionNotFound)?,
         };
 
-        // Get recipient wallet of pending transaction
-        let recipient_wallet = schema.wallet(&transaction.recipient).unwrap();
+        if !transaction.is_approved() {
+            Err(TxError::NotEnoughApprovals)?;
+        }
+
+        schema.remove_awaiting_tx(&self.tx_hash, &hash);
+
+        // Transaction which can't be executed anymore is rejected, so it isn't left pending
+        if let Err(error) = self.check_pending_tx(&schema, &wallet, transaction) {
+            schema.reject_pending_tx(&wallet, &self.tx_hash, error, &hash)?;
+            return Ok(());
+        }
+
+        match transaction.kind {
+            PendingKind::Transfer => {
+                // Get recipient wallet of pending transaction
+                let recipient_wallet = match schema.wallet(&transaction.recipient) {
+                    Some(val) => val,
+                    None => Err(TxError::RecipientNotFound)?,
+                };
+
+                let mut new_wallet = schema.confirm_pending_tx(
+                    &wallet,
+                    transaction,
+                    self.confirmation_block,
+                    &hash,
+                )?;
+                if transaction.within_limit {
+                    new_wallet = schema.add_spending(&new_wallet, transaction.amount, &hash);
+                }
+                schema.decrease_wallet_balance(
+                    &new_wallet,
+                    &transaction.asset_id,
+                    transaction.amount,
+                    &hash,
+                )?;
+                schema.increase_wallet_balance(
+                    &recipient_wallet,
+                    &transaction.asset_id,
+                    transaction.amount,
+                    &hash,
+                )?;
+                schema.add_incoming_tx(
+                    &transaction.recipient,
+                    IncomingTransaction {
+                        tx_hash: transaction.tx_hash,
+                        sender: self.sender,
+                        asset_id: transaction.asset_id.clone(),
+                        amount: transaction.amount,
+                        confirmation_block: self.confirmation_block,
+                    },
+                );
+            }
+            PendingKind::Mint => {
+                let new_wallet = schema.confirm_pending_tx(
+                    &wallet,
+                    transaction,
+                    self.confirmation_block,
+                    &hash,
+                )?;
+                schema.mint(&new_wallet, transaction.amount, &hash)?;
+            }
+            PendingKind::Burn => {
+                let new_wallet = schema.confirm_pending_tx(
+                    &wallet,
+                    transaction,
+                    self.confirmation_block,
+                    &hash,
+                )?;
+                schema.burn(&new_wallet, transaction.amount, &hash)?;
+            }
+            PendingKind::BatchTransfer => {
+                // Batch is executed entirely or not at all
+                let new_wallet = schema.confirm_pending_tx(
+                    &wallet,
+                    transaction,
+                    self.confirmation_block,
+                    &hash,
+                )?;
+                schema.decrease_wallet_balance(
+                    &new_wallet,
+                    &transaction.asset_id,
+                    transaction.amount,
+                    &hash,
+                )?;
+
+                for payment in &transaction.payments {
+                    // Recipient is loaded for every payment, as it may be paid more than once
+                    let recipient_wallet = match schema.wallet(&payment.recipient) {
+                        Some(val) => val,
+                        None => Err(TxError::RecipientNotFound)?,
+                    };
+                    schema.increase_wallet_balance(
+                        &recipient_wallet,
+                        &transaction.asset_id,
+                        payment.amount,
+                        &hash,
+                    )?;
+                    schema.add_incoming_tx(
+                        &payment.recipient,
+                        IncomingTransaction {
+                            tx_hash: transaction.tx_hash,
+                            sender: self.sender,
+                            asset_id: transaction.asset_id.clone(),
+                            amount: payment.amount,
+                            confirmation_block: self.confirmation_block,
+                        },
+                    );
+                }
+            }
+            PendingKind::SetSpendingLimit => {
+                let new_wallet = schema.confirm_pending_tx(
+                    &wallet,
+                    transaction,
+                    self.confirmation_block,
+                    &hash,
+                )?;
+                let spending_limit = SpendingLimit {
+                    amount: transaction.amount,
+                    period: transaction.period,
+                };
+                schema.set_spending_limit(&new_wallet, spending_limit, &hash);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::IssueAssetTx")]
+pub struct IssueAssetTx {
+    pub asset_id: String,
+    pub name: String,
+    pub amount: u64,
+    pub seed: u64,
+}
+
+impl Transaction for IssueAssetTx {
+    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+        let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
+
+        let wallet = match schema.wallet(pub_key) {
+            Some(val) => val,
+            None => Err(TxError::WalletNotFound)?,
+        };
+
+        if self.asset_id == NATIVE_ASSET_ID {
+            Err(TxError::InvalidAssetId)?;
+        }
+
+        // Only the first issuer of an asset may issue it further
+        if let Some(asset) = schema.asset(&self.asset_id) {
+            if asset.issuer != *pub_key {
+                Err(TxError::UnauthorizedIssuer)?;
+            }
+        }
+
+        schema.issue_asset(&wallet, &self.asset_id, &self.name, self.amount, &hash)?;
+        schema.charge_fee(pub_key, |fees| &mut fees.issue_asset, &hash)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::MintTx")]
+pub struct MintTx {
+    pub amount: u64,
+    pub seed: u64,
+}
+
+impl Transaction for MintTx {
+    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+        let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
+
+        if *pub_key != schema.config().issuer {
+            Err(TxError::UnauthorizedIssuer)?;
+        }
+
+        let wallet = match schema.wallet(pub_key) {
+            Some(val) => val,
+            None => Err(TxError::WalletNotFound)?,
+        };
+
+        if schema.total_supply().checked_add(self.amount).is_none() {
+            Err(TxError::SupplyOverflow)?;
+        }
 
-        schema.remove_awaiting_tx(&self.tx_hash);
-        let new_wallet =
-            schema.confirm_pending_tx(&wallet, &transaction, self.confirmation_block, &hash);
-        schema.decrease_wallet_balance(&new_wallet, transaction.amount, &hash);
-        schema.increase_wallet_balance(&recipient_wallet, transaction.amount, &hash);
+        if wallet.pending_txs.len() as u64 >= schema.config().max_pending_txs {
+            Err(TxError::TooManyPendingTransactions)?;
+        }
+
+        // Issuer multisig wallet mints only after signers approval
+        let pending_tx = PendingTransaction::new(
+            &hash,
+            PendingKind::Mint,
+            pub_key,
+            NATIVE_ASSET_ID,
+            self.amount,
+        )
+        .with_signers(&wallet.signers, schema.required_weight(&wallet))
+        .with_groups(&wallet.groups);
+        schema.add_pending_tx(&wallet, pending_tx, &hash);
+
+        if wallet.signers.is_empty() {
+            schema.add_awaiting_tx(&hash, &hash, pub_key);
+        }
 
+        schema.charge_fee(pub_key, |fees| &mut fees.mint, &hash)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::BurnTx")]
+pub struct BurnTx {
+    pub amount: u64,
+    pub seed: u64,
+}
+
+impl Transaction for BurnTx {
+    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+        let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
+
+        if *pub_key != schema.config().issuer {
+            Err(TxError::UnauthorizedIssuer)?;
+        }
+
+        let wallet = match schema.wallet(pub_key) {
+            Some(val) => val,
+            None => Err(TxError::WalletNotFound)?,
+        };
+
+        let fee = schema.config().fees.burn;
+        if !wallet.can_spend(NATIVE_ASSET_ID, self.amount, fee) {
+            Err(TxError::InsufficientCurrencyAmount)?;
+        }
+
+        if wallet.pending_txs.len() as u64 >= schema.config().max_pending_txs {
+            Err(TxError::TooManyPendingTransactions)?;
+        }
+
+        let pending_tx = PendingTransaction::new(
+            &hash,
+            PendingKind::Burn,
+            pub_key,
+            NATIVE_ASSET_ID,
+            self.amount,
+        )
+        .with_signers(&wallet.signers, schema.required_weight(&wallet))
+        .with_groups(&wallet.groups);
+        schema.add_pending_tx(&wallet, pending_tx, &hash);
+
+        if wallet.signers.is_empty() {
+            schema.add_awaiting_tx(&hash, &hash, pub_key);
+        }
+
+        schema.charge_fee(pub_key, |fees| &mut fees.burn, &hash)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::BatchTransferTx")]
+pub struct BatchTransferTx {
+    pub payments: Vec<Payment>,
+    pub asset_id: String,
+    pub seed: u64,
+}
+
+impl Transaction for BatchTransferTx {
+    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+        let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
+
+        let sender_wallet = match schema.wallet(pub_key) {
+            Some(val) => val,
+            None => Err(TxError::SenderNotFound)?,
+        };
+
+        if self.asset_id != NATIVE_ASSET_ID && schema.asset(&self.asset_id).is_none() {
+            Err(TxError::AssetNotFound)?;
+        }
+
+        if self.payments.is_empty() {
+            Err(TxError::InvalidBatch)?;
+        }
+
+        let total = match self
+            .payments
+            .iter()
+            .try_fold(0u64, |total, item| total.checked_add(item.amount))
+        {
+            Some(val) => val,
+            None => Err(TxError::InvalidBatch)?,
+        };
+
+        if self.payments.iter().any(|item| item.recipient == *pub_key) {
+            Err(TxError::SelfTransfer)?;
+        }
+
+        if self.payments.iter().any(|item| item.amount == 0) {
+            Err(TxError::ZeroAmount)?;
+        }
+
+        if self
+            .payments
+            .iter()
+            .any(|item| schema.wallet(&item.recipient).is_none())
+        {
+            Err(TxError::RecipientNotFound)?;
+        }
+
+        let fee = schema.config().fees.batch_transfer;
+        if !sender_wallet.can_spend(&self.asset_id, total, fee) {
+            Err(TxError::InsufficientCurrencyAmount)?;
+        }
+
+        if sender_wallet.pending_txs.len() as u64 >= schema.config().max_pending_txs {
+            Err(TxError::TooManyPendingTransactions)?;
+        }
+
+        // Whole batch is approved by signers once
+        let pending_tx = PendingTransaction {
+            payments: self.payments.clone(),
+            ..PendingTransaction::new(
+                &hash,
+                PendingKind::BatchTransfer,
+                pub_key,
+                &self.asset_id,
+                total,
+            )
+            .with_signers(
+                &sender_wallet.signers,
+                schema.required_weight(&sender_wallet),
+            )
+            .with_groups(&sender_wallet.groups)
+        };
+        schema.add_pending_tx(&sender_wallet, pending_tx, &hash);
+
+        if sender_wallet.signers.is_empty() {
+            schema.add_awaiting_tx(&hash, &hash, pub_key);
+        }
+
+        schema.charge_fee(pub_key, |fees| &mut fees.batch_transfer, &hash)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::SetSpendingLimitTx")]
+pub struct SetSpendingLimitTx {
+    pub amount: u64,
+    pub period: u64,
+    pub seed: u64,
+}
+
+impl Transaction for SetSpendingLimitTx {
+    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+        let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
+
+        let wallet = match schema.wallet(pub_key) {
+            Some(val) => val,
+            None => Err(TxError::WalletNotFound)?,
+        };
+
+        if self.amount > 0 && self.period == 0 {
+            Err(TxError::InvalidSpendingLimit)?;
+        }
+
+        if wallet.pending_txs.len() as u64 >= schema.config().max_pending_txs {
+            Err(TxError::TooManyPendingTransactions)?;
+        }
+
+        // Limit itself is changed only with signers approval
+        let pending_tx = PendingTransaction {
+            period: self.period,
+            ..PendingTransaction::new(
+                &hash,
+                PendingKind::SetSpendingLimit,
+                pub_key,
+                NATIVE_ASSET_ID,
+                self.amount,
+            )
+            .with_signers(&wallet.signers, schema.required_weight(&wallet))
+            .with_groups(&wallet.groups)
+        };
+        schema.add_pending_tx(&wallet, pending_tx, &hash);
+
+        if wallet.signers.is_empty() {
+            schema.add_awaiting_tx(&hash, &hash, pub_key);
+        }
+
+        schema.charge_fee(pub_key, |fees| &mut fees.set_spending_limit, &hash)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::ProposeServiceConfigTx")]
+pub struct ProposeServiceConfigTx {
+    pub config: ServiceConfig,
+    pub activation_height: u64,
+}
+
+impl Transaction for ProposeServiceConfigTx {
+    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+        let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
+
+        if !schema.validators().contains(pub_key) {
+            Err(TxError::NotValidator)?;
+        }
+
+        // Service name can't be changed, API is mounted under it once at start
+        if self.config.validate().is_err()
+            || self.config.service_name != schema.config().service_name
+            || schema.wallet(&self.config.treasury).is_none()
+        {
+            Err(TxError::InvalidServiceConfig)?;
+        }
+
+        if self.activation_height <= schema.current_height() {
+            Err(TxError::InvalidActivationHeight)?;
+        }
+
+        let proposal = ConfigProposal::new(self.config.clone(), self.activation_height, pub_key);
+        schema.add_config_proposal(&hash, proposal);
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert)]
+#[exonum(pb = "proto::VoteServiceConfigTx")]
+pub struct VoteServiceConfigTx {
+    pub proposal_hash: Hash,
+}
+
+impl Transaction for VoteServiceConfigTx {
+    fn execute(&self, mut context: TransactionContext) -> ExecutionResult {
+        let pub_key = &context.author();
+        let hash = context.tx_hash();
+        let mut schema = Schema::new(context.fork());
+        schema.create_receipt(&hash);
+
+        if !schema.validators().contains(pub_key) {
+            Err(TxError::NotValidator)?;
+        }
+
+        let proposal = match schema.config_proposal(&self.proposal_hash) {
+            Some(val) => val,
+            None => Err(TxError::ConfigProposalNotFound)?,
+        };
+
+        if proposal.votes.contains(pub_key) {
+            Err(TxError::AlreadyVoted)?;
+        }
+
+        schema.vote_config_proposal(&self.proposal_hash, &proposal, pub_key);
         Ok(())
     }
 }
@@ -247,6 +1253,84 @@ pub enum TxError {
 
     #[fail(display = "Already signed")]
     AlreadySigned = 7,
+
+    #[fail(display = "Asset not found")]
+    AssetNotFound = 8,
+
+    #[fail(display = "Invalid asset id")]
+    InvalidAssetId = 9,
+
+    #[fail(display = "Unauthorized issuer")]
+    UnauthorizedIssuer = 10,
+
+    #[fail(display = "Supply overflow")]
+    SupplyOverflow = 11,
+
+    #[fail(display = "Too many signers")]
+    TooManySigners = 12,
+
+    #[fail(display = "Too many pending transactions")]
+    TooManyPendingTransactions = 13,
+
+    #[fail(display = "Not a validator")]
+    NotValidator = 14,
+
+    #[fail(display = "Invalid service config")]
+    InvalidServiceConfig = 15,
+
+    #[fail(display = "Invalid activation height")]
+    InvalidActivationHeight = 16,
+
+    #[fail(display = "Config proposal not found")]
+    ConfigProposalNotFound = 17,
+
+    #[fail(display = "Already voted")]
+    AlreadyVoted = 18,
+
+    #[fail(display = "Invalid spending limit")]
+    InvalidSpendingLimit = 19,
+
+    #[fail(display = "Invalid batch")]
+    InvalidBatch = 20,
+
+    #[fail(display = "Transfer amount is zero")]
+    ZeroAmount = 21,
+
+    #[fail(display = "Transfer to the sender wallet")]
+    SelfTransfer = 22,
+
+    #[fail(display = "Balance overflow")]
+    BalanceOverflow = 23,
+
+    #[fail(display = "Key is already a signer")]
+    AlreadySigner = 24,
+
+    #[fail(display = "Key is already invited")]
+    AlreadyInvited = 25,
+
+    #[fail(display = "Signer invite not found")]
+    InviteNotFound = 26,
+
+    #[fail(display = "Not enough approvals")]
+    NotEnoughApprovals = 27,
+
+    #[fail(display = "Signer weight is zero")]
+    ZeroWeight = 28,
+
+    #[fail(display = "Group member is not a wallet signer")]
+    GroupMemberNotFound = 29,
+
+    #[fail(display = "Group threshold exceeds the weight of its members")]
+    UnreachableGroupThreshold = 30,
+
+    #[fail(display = "Signer group not found")]
+    GroupNotFound = 31,
+
+    #[fail(display = "Too many signer groups")]
+    TooManyGroups = 32,
+
+    #[fail(display = "Approval not found")]
+    ApprovalNotFound = 33,
 }
 
 impl From<TxError> for ExecutionError {
@@ -1,18 +1,89 @@
+use super::asset::{AssetBalance, NATIVE_ASSET_ID};
 use super::proto;
-use super::transaction::{ApprovedTransaction, PendingTransaction};
+use super::transaction::{ApprovedTransaction, PendingTransaction, TxError};
 use exonum::crypto::{Hash, PublicKey};
 use exonum_derive::ProtobufConvert;
 use serde_derive::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, ProtobufConvert, Serialize, Deserialize)]
+/// Amount of native coins the wallet may transfer without signers approval
+/// within the last `period` blocks, zero amount disables the limit.
+#[derive(Clone, Debug, Default, PartialEq, ProtobufConvert, Serialize, Deserialize)]
+#[exonum(pb = "proto::SpendingLimit")]
+pub struct SpendingLimit {
+    pub amount: u64,
+    pub period: u64,
+}
+
+/// Wallet signer, its approval adds `weight` to the approval weight of a transaction.
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Serialize, Deserialize)]
+#[exonum(pb = "proto::Signer")]
+pub struct Signer {
+    pub pub_key: PublicKey,
+    pub weight: u64,
+}
+
+impl Signer {
+    pub fn new(&pub_key: &PublicKey, weight: u64) -> Self {
+        Self { pub_key, weight }
+    }
+}
+
+/// Returns the signer with the given key.
+pub fn find_signer<'a>(signers: &'a [Signer], pub_key: &PublicKey) -> Option<&'a Signer> {
+    signers.iter().find(|item| item.pub_key == *pub_key)
+}
+
+/// Returns the sum of the signers weights.
+pub fn total_weight(signers: &[Signer]) -> u64 {
+    signers
+        .iter()
+        .fold(0, |total, item| total.saturating_add(item.weight))
+}
+
+/// Named group of wallet signers, a transaction of the wallet is executed only once
+/// its members approvals weigh at least `threshold`, in addition to the wallet threshold.
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Serialize, Deserialize)]
+#[exonum(pb = "proto::SignerGroup")]
+pub struct SignerGroup {
+    pub name: String,
+    pub members: Vec<PublicKey>,
+    pub threshold: u64,
+}
+
+impl SignerGroup {
+    /// Returns the sum of weights of the group members among `signers`.
+    pub fn members_weight(&self, signers: &[Signer]) -> u64 {
+        signers
+            .iter()
+            .filter(|item| self.members.contains(&item.pub_key))
+            .fold(0, |total, item| total.saturating_add(item.weight))
+    }
+}
+
+/// Transfer executed under the spending limit.
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Serialize, Deserialize)]
+#[exonum(pb = "proto::Spending")]
+pub struct Spending {
+    pub height: u64,
+    pub amount: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, Serialize, Deserialize)]
 #[exonum(pb = "proto::Wallet")]
 pub struct Wallet {
     pub pub_key: PublicKey,
     pub name: String,
     pub balance: u64,
-    pub signers: Vec<PublicKey>,
+    pub signers: Vec<Signer>,
+    /// Keys invited as signers, they count toward thresholds once they accept the invite.
+    pub invites: Vec<Signer>,
+    /// Groups of signers, each of them approves a transaction with its own threshold.
+    pub groups: Vec<SignerGroup>,
     pub pending_txs: Vec<PendingTransaction>,
     pub txs: Vec<ApprovedTransaction>,
+    pub assets: Vec<AssetBalance>,
+    pub spending_limit: SpendingLimit,
+    pub spendings: Vec<Spending>,
     pub history_len: u64,
     pub history_hash: Hash,
 }
@@ -22,9 +93,14 @@ impl Wallet {
         &pub_key: &PublicKey,
         name: &str,
         balance: u64,
-        signers: Vec<PublicKey>,
+        signers: Vec<Signer>,
+        invites: Vec<Signer>,
+        groups: Vec<SignerGroup>,
         pending_txs: Vec<PendingTransaction>,
         txs: Vec<ApprovedTransaction>,
+        assets: Vec<AssetBalance>,
+        spending_limit: SpendingLimit,
+        spendings: Vec<Spending>,
         history_len: u64,
         &history_hash: &Hash,
     ) -> Self {
@@ -33,42 +109,159 @@ impl Wallet {
             name: name.to_owned(),
             balance,
             signers,
+            invites,
+            groups,
             pending_txs,
             txs,
+            assets,
+            spending_limit,
+            spendings,
             history_len,
             history_hash,
         }
     }
 
-    pub fn set_balance(self, balance: u64, history_hash: &Hash) -> Self {
+    /// Returns the wallet balance of the given asset.
+    pub fn balance_of(&self, asset_id: &str) -> u64 {
+        if asset_id == NATIVE_ASSET_ID {
+            return self.balance;
+        }
+
+        self.assets
+            .iter()
+            .find(|item| item.asset_id == asset_id)
+            .map_or(0, |item| item.amount)
+    }
+
+    /// Returns `true` if the wallet holds `amount` of the asset and `fee` native coins on top of it.
+    pub fn can_spend(&self, asset_id: &str, amount: u64, fee: u64) -> bool {
+        if asset_id == NATIVE_ASSET_ID {
+            return amount
+                .checked_add(fee)
+                .map_or(false, |total| self.balance >= total);
+        }
+
+        self.balance_of(asset_id) >= amount && self.balance >= fee
+    }
+
+    pub fn set_balance(self, asset_id: &str, balance: u64, history_hash: &Hash) -> Self {
+        let mut assets = self.assets.clone();
+        let native_balance = if asset_id == NATIVE_ASSET_ID {
+            balance
+        } else {
+            match assets.iter().position(|item| item.asset_id == asset_id) {
+                Some(index) => assets[index].amount = balance,
+                None => assets.push(AssetBalance {
+                    asset_id: asset_id.to_owned(),
+                    amount: balance,
+                }),
+            }
+            self.balance
+        };
+
         Self::new(
             &self.pub_key,
             &self.name,
-            balance,
+            native_balance,
             self.signers,
+            self.invites,
+            self.groups,
             self.pending_txs,
             self.txs,
+            assets,
+            self.spending_limit,
+            self.spendings,
             self.history_len + 1,
             history_hash,
         )
     }
 
-    pub fn add_signer(self, pub_key: &PublicKey, history_hash: &Hash) -> Self {
-        let mut signers = self.signers.clone();
-        signers.push(*pub_key);
+    pub fn is_signer(&self, pub_key: &PublicKey) -> bool {
+        find_signer(&self.signers, pub_key).is_some()
+    }
+
+    pub fn is_invited(&self, pub_key: &PublicKey) -> bool {
+        find_signer(&self.invites, pub_key).is_some()
+    }
+
+    pub fn invite_signer(self, signer: Signer, history_hash: &Hash) -> Self {
+        let mut invites = self.invites.clone();
+        invites.push(signer);
 
         Self::new(
             &self.pub_key,
             &self.name,
             self.balance,
-            signers,
+            self.signers,
+            invites,
+            self.groups,
+            self.pending_txs,
+            self.txs,
+            self.assets,
+            self.spending_limit,
+            self.spendings,
+            self.history_len + 1,
+            history_hash,
+        )
+    }
+
+    /// Replaces the group with the same name, the group with zero threshold is removed.
+    pub fn set_signer_group(self, group: SignerGroup, history_hash: &Hash) -> Self {
+        let mut groups: Vec<_> = self
+            .groups
+            .iter()
+            .filter(|item| item.name != group.name)
+            .cloned()
+            .collect();
+        if group.threshold > 0 {
+            groups.push(group);
+        }
+
+        Self::new(
+            &self.pub_key,
+            &self.name,
+            self.balance,
+            self.signers,
+            self.invites,
+            groups,
             self.pending_txs,
             self.txs,
+            self.assets,
+            self.spending_limit,
+            self.spendings,
             self.history_len + 1,
             history_hash,
         )
     }
 
+    /// Moves the invited key to the signers.
+    pub fn accept_invite(self, pub_key: &PublicKey, history_hash: &Hash) -> Result<Self, TxError> {
+        let mut invites = self.invites.clone();
+        let invite_index = invites
+            .iter()
+            .position(|item| item.pub_key == *pub_key)
+            .ok_or(TxError::InviteNotFound)?;
+
+        let mut signers = self.signers.clone();
+        signers.push(invites.remove(invite_index));
+
+        Ok(Self::new(
+            &self.pub_key,
+            &self.name,
+            self.balance,
+            signers,
+            invites,
+            self.groups,
+            self.pending_txs,
+            self.txs,
+            self.assets,
+            self.spending_limit,
+            self.spendings,
+            self.history_len + 1,
+            history_hash,
+        ))
+    }
+
     pub fn add_pending_tx(self, tx: PendingTransaction, history_hash: &Hash) -> Self {
         let mut pending_txs = self.pending_txs.clone();
         pending_txs.push(tx);
@@ -78,65 +271,193 @@ impl Wallet {
             &self.name,
             self.balance,
             self.signers,
+            self.invites,
+            self.groups,
             pending_txs,
             self.txs,
+            self.assets,
+            self.spending_limit,
+            self.spendings,
             self.history_len + 1,
             history_hash,
         )
     }
 
-    pub fn sign_pending_tx(self, tx_hash: &Hash, signer: &PublicKey, history_hash: &Hash) -> Self {
+    pub fn sign_pending_tx(
+        self,
+        tx_hash: &Hash,
+        signer: &PublicKey,
+        history_hash: &Hash,
+    ) -> Result<Self, TxError> {
         let mut pending_txs = self.pending_txs.clone();
         let tx_index = pending_txs
             .iter()
             .position(|item| item.tx_hash == *tx_hash)
-            .unwrap();
-        let tx = &mut pending_txs[tx_index];
-        tx.approvals.push(*signer);
+            .ok_or(TxError::PendingTransactionNotFound)?;
+        pending_txs[tx_index].approve(signer)?;
 
-        Self::new(
+        Ok(Self::new(
             &self.pub_key,
             &self.name,
             self.balance,
             self.signers,
+            self.invites,
+            self.groups,
             pending_txs,
             self.txs,
+            self.assets,
+            self.spending_limit,
+            self.spendings,
             self.history_len + 1,
             history_hash,
-        )
+        ))
     }
 
-    pub fn remove_pending_tx(self, tx_hash: &Hash, history_hash: &Hash) -> Self {
+    pub fn revoke_approval(
+        self,
+        tx_hash: &Hash,
+        signer: &PublicKey,
+        history_hash: &Hash,
+    ) -> Result<Self, TxError> {
         let mut pending_txs = self.pending_txs.clone();
         let tx_index = pending_txs
             .iter()
             .position(|item| item.tx_hash == *tx_hash)
-            .unwrap();
+            .ok_or(TxError::PendingTransactionNotFound)?;
+        pending_txs[tx_index].revoke(signer)?;
+
+        Ok(Self::new(
+            &self.pub_key,
+            &self.name,
+            self.balance,
+            self.signers,
+            self.invites,
+            self.groups,
+            pending_txs,
+            self.txs,
+            self.assets,
+            self.spending_limit,
+            self.spendings,
+            self.history_len + 1,
+            history_hash,
+        ))
+    }
+
+    /// Removes the rejected pending transaction.
+    pub fn reject_pending_tx(self, tx_hash: &Hash, history_hash: &Hash) -> Result<Self, TxError> {
+        let mut pending_txs = self.pending_txs.clone();
+        let tx_index = pending_txs
+            .iter()
+            .position(|item| item.tx_hash == *tx_hash)
+            .ok_or(TxError::PendingTransactionNotFound)?;
         pending_txs.remove(tx_index);
 
-        Self::new(
+        Ok(Self::new(
             &self.pub_key,
             &self.name,
             self.balance,
             self.signers,
+            self.invites,
+            self.groups,
             pending_txs,
             self.txs,
+            self.assets,
+            self.spending_limit,
+            self.spendings,
             self.history_len + 1,
             history_hash,
-        )
+        ))
     }
 
-    pub fn add_approved_tx(self, tx: ApprovedTransaction, history_hash: &Hash) -> Self {
+    pub fn confirm_pending_tx(
+        self,
+        tx: ApprovedTransaction,
+        history_hash: &Hash,
+    ) -> Result<Self, TxError> {
+        let mut pending_txs = self.pending_txs.clone();
+        let tx_index = pending_txs
+            .iter()
+            .position(|item| item.tx_hash == tx.tx_hash)
+            .ok_or(TxError::PendingTransactionNotFound)?;
+        pending_txs.remove(tx_index);
+
         let mut txs = self.txs.clone();
         txs.push(tx);
 
+        Ok(Self::new(
+            &self.pub_key,
+            &self.name,
+            self.balance,
+            self.signers,
+            self.invites,
+            self.groups,
+            pending_txs,
+            txs,
+            self.assets,
+            self.spending_limit,
+            self.spendings,
+            self.history_len + 1,
+            history_hash,
+        ))
+    }
+
+    /// Returns the amount transferred under the spending limit within its period before `height`,
+    /// including pending transfers within the limit, or `None` if the sum overflows.
+    pub fn spent_before(&self, height: u64) -> Option<u64> {
+        let pending = self
+            .pending_txs
+            .iter()
+            .filter(|item| item.within_limit)
+            .map(|item| item.amount);
+        self.spendings
+            .iter()
+            .filter(|item| item.height.saturating_add(self.spending_limit.period) > height)
+            .map(|item| item.amount)
+            .chain(pending)
+            .try_fold(0u64, |spent, amount| spent.checked_add(amount))
+    }
+
+    pub fn set_spending_limit(self, spending_limit: SpendingLimit, history_hash: &Hash) -> Self {
         Self::new(
             &self.pub_key,
             &self.name,
             self.balance,
             self.signers,
+            self.invites,
+            self.groups,
             self.pending_txs,
-            txs,
+            self.txs,
+            self.assets,
+            spending_limit,
+            self.spendings,
+            self.history_len + 1,
+            history_hash,
+        )
+    }
+
+    pub fn add_spending(self, spending: Spending, history_hash: &Hash) -> Self {
+        // Spendings out of the limit period are not needed anymore
+        let period = self.spending_limit.period;
+        let mut spendings: Vec<_> = self
+            .spendings
+            .iter()
+            .filter(|item| item.height.saturating_add(period) > spending.height)
+            .cloned()
+            .collect();
+        spendings.push(spending);
+
+        Self::new(
+            &self.pub_key,
+            &self.name,
+            self.balance,
+            self.signers,
+            self.invites,
+            self.groups,
+            self.pending_txs,
+            self.txs,
+            self.assets,
+            self.spending_limit,
+            spendings,
             self.history_len + 1,
             history_hash,
         )
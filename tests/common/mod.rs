@@ -0,0 +1,249 @@
+//! Service test harness on top of `exonum-testkit`, shared with the fuzz targets.
+//!
+//! Blocks are created from the given transactions and the transactions broadcasted
+//! by the service in `after_commit` of the previous block.
+
+#![allow(dead_code)]
+
+use exonum::api::ServiceApiState;
+use exonum::blockchain::{
+    Blockchain, Schema as BlockchainSchema, TransactionErrorType, TransactionResult,
+};
+use exonum::crypto::{self, Hash, PublicKey, SecretKey};
+use exonum::messages::{Message, RawTransaction, ServiceTransaction, Signed};
+use exonum::storage::{Database, MemoryDB, ProofListIndex};
+use exonum_multisig::api::{Api, WalletQuery};
+use exonum_multisig::asset::NATIVE_ASSET_ID;
+use exonum_multisig::config::ServiceConfig;
+use exonum_multisig::schema::Schema;
+use exonum_multisig::service::{Service, SERVICE_ID};
+use exonum_multisig::transaction::{
+    AcceptSignerInviteTx, AddSignerTx, CreateWalletTx, SetSignerGroupTx, TransferTx, TxError,
+};
+use exonum_multisig::wallet::Wallet;
+use exonum_testkit::{TestKit as ExonumTestKit, TestKitBuilder};
+
+pub type KeyPair = (PublicKey, SecretKey);
+
+pub struct TestKit {
+    testkit: ExonumTestKit,
+    /// Shares the database with the testkit blockchain.
+    blockchain: Blockchain,
+    issuer: KeyPair,
+    /// Transactions broadcasted by the service, included into the next block.
+    pool: Vec<Signed<RawTransaction>>,
+}
+
+impl TestKit {
+    pub fn new() -> Self {
+        Self::with_config(|_| {})
+    }
+
+    /// Creates blockchain with the service config adjusted by `update`.
+    ///
+    /// Issuer and treasury are the `issuer` key by default, as validator keys
+    /// are generated by the testkit after the service is created.
+    pub fn with_config<F>(update: F) -> Self
+    where
+        F: FnOnce(&mut ServiceConfig),
+    {
+        let issuer = crypto::gen_keypair();
+
+        let mut config = ServiceConfig::new(&issuer.0);
+        update(&mut config);
+
+        let mut testkit = TestKitBuilder::validator()
+            .with_service(Service::new(config))
+            .create();
+        let blockchain = testkit.blockchain_mut().clone();
+
+        Self {
+            testkit,
+            blockchain,
+            issuer,
+            pool: vec![],
+        }
+    }
+
+    /// Service keys of the only validator, which signs the broadcasted transactions.
+    pub fn validator(&self) -> KeyPair {
+        let (pub_key, secret_key) = self.testkit.us().service_keypair();
+        (pub_key, secret_key.clone())
+    }
+
+    pub fn issuer(&self) -> KeyPair {
+        self.issuer.clone()
+    }
+
+    pub fn blockchain(&self) -> &Blockchain {
+        &self.blockchain
+    }
+
+    pub fn api(&self) -> ServiceApiState {
+        ServiceApiState::new(self.blockchain.clone())
+    }
+
+    pub fn height(&self) -> u64 {
+        self.testkit.height().0
+    }
+
+    /// Transactions broadcasted by the service and not included into a block yet.
+    pub fn pool(&self) -> &[Signed<RawTransaction>] {
+        &self.pool
+    }
+
+    /// Creates a block with the pool transactions followed by `txs`.
+    pub fn create_block(&mut self, txs: Vec<Signed<RawTransaction>>) {
+        let txs: Vec<_> = self.pool.drain(..).chain(txs).collect();
+        self.testkit.create_block_with_transactions(txs);
+
+        // Testkit puts the broadcasted transactions into the blockchain pool
+        self.testkit.poll_events();
+        self.update_pool();
+    }
+
+    /// Remembers the blockchain state to return to with `rollback`.
+    pub fn checkpoint(&mut self) {
+        self.testkit.checkpoint();
+    }
+
+    /// Returns the blockchain to the state of the latest checkpoint.
+    pub fn rollback(&mut self) {
+        self.testkit.rollback();
+        self.update_pool();
+    }
+
+    fn update_pool(&mut self) {
+        let snapshot = self.blockchain.snapshot();
+        let schema = BlockchainSchema::new(&snapshot);
+        let transactions = schema.transactions();
+        self.pool = schema
+            .transactions_pool()
+            .iter()
+            .filter_map(|hash| transactions.get(&hash))
+            .collect();
+    }
+
+    /// Creates a block with `txs` and one more with the confirmations they produced.
+    pub fn create_confirmed_block(&mut self, txs: Vec<Signed<RawTransaction>>) {
+        self.create_block(txs);
+        self.create_block(vec![]);
+    }
+
+    pub fn wallet(&self, pub_key: &PublicKey) -> Wallet {
+        Api::get_wallet(&self.api(), WalletQuery { pub_key: *pub_key }).unwrap()
+    }
+
+    pub fn find_wallet(&self, pub_key: &PublicKey) -> Option<Wallet> {
+        Api::get_wallet(&self.api(), WalletQuery { pub_key: *pub_key }).ok()
+    }
+
+    /// Hashes of the pending transactions awaiting confirmation.
+    pub fn awaiting_txs(&self) -> Vec<Hash> {
+        Schema::new(self.blockchain.snapshot())
+            .awaiting_txs()
+            .keys()
+            .collect()
+    }
+
+    pub fn tx_result(&self, tx: &Signed<RawTransaction>) -> TransactionResult {
+        BlockchainSchema::new(&self.blockchain.snapshot())
+            .transaction_results()
+            .get(&tx.hash())
+            .expect("Transaction is not committed")
+    }
+
+    pub fn assert_tx_success(&self, tx: &Signed<RawTransaction>) {
+        if let Err(e) = self.tx_result(tx).0 {
+            panic!("Transaction failed: {:?}", e);
+        }
+    }
+
+    pub fn assert_tx_error(&self, tx: &Signed<RawTransaction>, error: TxError) {
+        let expected = error as u8;
+        match self.tx_result(tx).0 {
+            Ok(()) => panic!("Transaction succeeded, expected error {}", expected),
+            Err(e) => assert_eq!(e.error_type(), TransactionErrorType::Code(expected)),
+        }
+    }
+}
+
+/// Returns the merkle root of the wallet history with the given transactions.
+pub fn history_hash(txs: &[&Signed<RawTransaction>]) -> Hash {
+    let db = MemoryDB::new();
+    let mut fork = db.fork();
+    let mut history = ProofListIndex::new("history", &mut fork);
+    for tx in txs {
+        history.push(tx.hash());
+    }
+    history.merkle_root()
+}
+
+pub fn sign<T>(tx: T, keys: &KeyPair) -> Signed<RawTransaction>
+where
+    T: Into<ServiceTransaction>,
+{
+    Message::sign_transaction(tx, SERVICE_ID, keys.0, &keys.1)
+}
+
+pub fn create_wallet(keys: &KeyPair, name: &str) -> Signed<RawTransaction> {
+    sign(
+        CreateWalletTx {
+            name: name.to_owned(),
+        },
+        keys,
+    )
+}
+
+pub fn transfer(keys: &KeyPair, to: &KeyPair, amount: u64) -> Signed<RawTransaction> {
+    sign(
+        TransferTx {
+            recipient: to.0,
+            asset_id: NATIVE_ASSET_ID.to_owned(),
+            amount,
+            seed: 0,
+        },
+        keys,
+    )
+}
+
+/// Returns the invite of `signer` to the `owner` wallet and its acceptance, in this order.
+pub fn add_signer(
+    owner: &KeyPair,
+    signer: &KeyPair,
+) -> (Signed<RawTransaction>, Signed<RawTransaction>) {
+    add_weighted_signer(owner, signer, 1)
+}
+
+/// Same as `add_signer`, the signer approvals weigh `weight`.
+pub fn add_weighted_signer(
+    owner: &KeyPair,
+    signer: &KeyPair,
+    weight: u64,
+) -> (Signed<RawTransaction>, Signed<RawTransaction>) {
+    let invite = sign(
+        AddSignerTx {
+            signer: signer.0,
+            weight,
+        },
+        owner,
+    );
+    let accept = sign(AcceptSignerInviteTx { wallet: owner.0 }, signer);
+    (invite, accept)
+}
+
+pub fn set_signer_group(
+    owner: &KeyPair,
+    name: &str,
+    members: &[KeyPair],
+    threshold: u64,
+) -> Signed<RawTransaction> {
+    sign(
+        SetSignerGroupTx {
+            name: name.to_owned(),
+            members: members.iter().map(|member| member.0).collect(),
+            threshold,
+        },
+        owner,
+    )
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::TestKit;
+use exonum::blockchain::TransactionSet;
+use exonum::crypto::{Hash, PublicKey};
+use exonum::messages::{Message, ProtocolMessage, RawTransaction, Signed};
+use exonum_multisig::transaction::WalletTransaction;
+use serde_json::Value;
+
+/// Scenario written by `cargo run --example fixtures`.
+const SCENARIO: &str = include_str!("../example-data/scenario.json");
+
+fn decode(fixture: &Value) -> Signed<RawTransaction> {
+    let bytes = hex::decode(fixture["signed"].as_str().unwrap()).unwrap();
+    let message = Message::from_raw_buffer(bytes).unwrap();
+    let tx = RawTransaction::try_from(message).unwrap();
+
+    let tx_hash: Hash = serde_json::from_value(fixture["tx_hash"].clone()).unwrap();
+    let author: PublicKey = serde_json::from_value(fixture["author"].clone()).unwrap();
+    assert_eq!(tx.hash(), tx_hash, "Hash of {}", fixture["title"]);
+    assert_eq!(tx.author(), author, "Author of {}", fixture["title"]);
+
+    let transaction = WalletTransaction::tx_from_raw(tx.payload().clone()).unwrap();
+    assert_eq!(
+        serde_json::to_value(transaction).unwrap(),
+        fixture["transaction"],
+        "Payload of {}",
+        fixture["title"]
+    );
+    tx
+}
+
+#[test]
+fn example_data_replays() {
+    let scenario: Value = serde_json::from_str(SCENARIO).unwrap();
+    let initial_balance = scenario["service_config"]["initial_balance"]
+        .as_u64()
+        .unwrap();
+    let mut kit = TestKit::with_config(|config| config.initial_balance = initial_balance);
+
+    for step in scenario["steps"].as_array().unwrap() {
+        let txs: Vec<_> = step["transactions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(decode)
+            .collect();
+        kit.create_block(txs.clone());
+        for tx in &txs {
+            kit.assert_tx_success(tx);
+        }
+    }
+    // Confirmation of the signed transfer
+    kit.create_block(vec![]);
+    assert!(kit.pool().is_empty());
+
+    for wallet in scenario["wallets"].as_array().unwrap() {
+        let pub_key: PublicKey = serde_json::from_value(wallet["pub_key"].clone()).unwrap();
+        let expected = wallet["balance"].as_u64().unwrap();
+        assert_eq!(
+            kit.wallet(&pub_key).balance,
+            expected,
+            "Balance of {}",
+            wallet["name"]
+        );
+    }
+}
@@ -0,0 +1,879 @@
+mod common;
+
+use common::{
+    add_signer, add_weighted_signer, create_wallet, history_hash, set_signer_group, sign, transfer,
+    TestKit,
+};
+use exonum::blockchain::{Blockchain, Schema as BlockchainSchema, TransactionSet};
+use exonum::crypto::{self, CryptoHash};
+use exonum::messages::{RawTransaction, Signed};
+use exonum_multisig::api::{
+    Api, BlockReceiptsQuery, ReceiptQuery, SimulationStatus, TransactionHex, WalletsQuery,
+};
+use exonum_multisig::asset::NATIVE_ASSET_ID;
+use exonum_multisig::receipt::{Receipt, Rejection};
+use exonum_multisig::schema::Schema;
+use exonum_multisig::service::SERVICE_ID;
+use exonum_multisig::transaction::{
+    ApprovedTransaction, ConfirmationTx, PendingKind, PendingTransaction, ProposeServiceConfigTx,
+    RevokeApprovalTx, SetSpendingLimitTx, SignTx, TxError, WalletTransaction,
+};
+use exonum_multisig::wallet::{Signer, Spending, SpendingLimit, Wallet};
+
+fn confirmation(tx: &Signed<RawTransaction>) -> ConfirmationTx {
+    match WalletTransaction::tx_from_raw(tx.payload().clone()).unwrap() {
+        WalletTransaction::Confirmation(confirmation) => confirmation,
+        other => panic!("Expected confirmation, got {:?}", other),
+    }
+}
+
+#[test]
+fn create_wallet_flow() {
+    let mut kit = TestKit::with_config(|config| config.initial_balance = 100);
+    let alice = crypto::gen_keypair();
+
+    let tx = create_wallet(&alice, "Alice");
+    kit.create_block(vec![tx.clone()]);
+    kit.assert_tx_success(&tx);
+
+    let expected = Wallet::new(
+        &alice.0,
+        "Alice",
+        100,
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        SpendingLimit::default(),
+        vec![],
+        1,
+        &history_hash(&[&tx]),
+    );
+    assert_eq!(kit.wallet(&alice.0), expected);
+    assert!(kit.pool().is_empty());
+}
+
+#[test]
+fn add_signer_flow() {
+    let mut kit = TestKit::new();
+    let alice = crypto::gen_keypair();
+    let bob = crypto::gen_keypair();
+
+    let create_tx = create_wallet(&alice, "Alice");
+    let (invite_tx, accept_tx) = add_signer(&alice, &bob);
+    kit.create_block(vec![create_tx.clone(), invite_tx.clone()]);
+    kit.assert_tx_success(&invite_tx);
+
+    // Invited key is not a signer until it accepts the invite
+    let expected = Wallet::new(
+        &alice.0,
+        "Alice",
+        0,
+        vec![],
+        vec![Signer::new(&bob.0, 1)],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        SpendingLimit::default(),
+        vec![],
+        2,
+        &history_hash(&[&create_tx, &invite_tx]),
+    );
+    assert_eq!(kit.wallet(&alice.0), expected);
+
+    kit.create_block(vec![accept_tx.clone()]);
+    kit.assert_tx_success(&accept_tx);
+
+    let expected = Wallet::new(
+        &alice.0,
+        "Alice",
+        0,
+        vec![Signer::new(&bob.0, 1)],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        SpendingLimit::default(),
+        vec![],
+        3,
+        &history_hash(&[&create_tx, &invite_tx, &accept_tx]),
+    );
+    assert_eq!(kit.wallet(&alice.0), expected);
+}
+
+#[test]
+fn immediate_transfer_without_signers() {
+    let mut kit = TestKit::with_config(|config| config.initial_balance = 100);
+    let alice = crypto::gen_keypair();
+    let bob = crypto::gen_keypair();
+
+    let alice_tx = create_wallet(&alice, "Alice");
+    let bob_tx = create_wallet(&bob, "Bob");
+    kit.create_block(vec![alice_tx.clone(), bob_tx.clone()]);
+
+    let transfer_tx = transfer(&alice, &bob, 30);
+    kit.create_block(vec![transfer_tx.clone()]);
+    kit.assert_tx_success(&transfer_tx);
+
+    // Transfer is pending until the confirmation is committed
+    let pending = kit.wallet(&alice.0);
+    assert_eq!(pending.balance, 100);
+    assert_eq!(
+        pending.pending_txs,
+        vec![PendingTransaction::new(
+            &transfer_tx.hash(),
+            PendingKind::Transfer,
+            &bob.0,
+            NATIVE_ASSET_ID,
+            30,
+        )]
+    );
+    assert_eq!(kit.pool().len(), 1);
+    let confirmation_tx = kit.pool()[0].clone();
+
+    kit.create_block(vec![]);
+    kit.assert_tx_success(&confirmation_tx);
+
+    let alice_wallet = kit.wallet(&alice.0);
+    assert_eq!(alice_wallet.balance, 70);
+    assert!(alice_wallet.pending_txs.is_empty());
+    assert_eq!(
+        alice_wallet.txs,
+        vec![ApprovedTransaction {
+            tx_hash: transfer_tx.hash(),
+            kind: PendingKind::Transfer,
+            recipient: bob.0,
+            asset_id: NATIVE_ASSET_ID.to_owned(),
+            amount: 30,
+            period: 0,
+            payments: vec![],
+            approvals: vec![],
+            confirmation_block: 2,
+        }]
+    );
+    assert_eq!(alice_wallet.history_len, 4);
+    assert_eq!(
+        alice_wallet.history_hash,
+        history_hash(&[&alice_tx, &transfer_tx, &confirmation_tx, &confirmation_tx])
+    );
+
+    let bob_wallet = kit.wallet(&bob.0);
+    assert_eq!(bob_wallet.balance, 130);
+    assert_eq!(bob_wallet.history_len, 2);
+    assert_eq!(
+        bob_wallet.history_hash,
+        history_hash(&[&bob_tx, &confirmation_tx])
+    );
+    assert!(kit.pool().is_empty());
+}
+
+#[test]
+fn threshold_transfer_through_sign() {
+    let mut kit = TestKit::with_config(|config| config.initial_balance = 100);
+    let alice = crypto::gen_keypair();
+    let bob = crypto::gen_keypair();
+    let signers: Vec<_> = (0..3).map(|_| crypto::gen_keypair()).collect();
+
+    let alice_tx = create_wallet(&alice, "Alice");
+    let bob_tx = create_wallet(&bob, "Bob");
+    let (invite_txs, accept_txs): (Vec<_>, Vec<_>) = signers
+        .iter()
+        .map(|signer| add_signer(&alice, signer))
+        .unzip();
+    let mut txs = vec![alice_tx.clone(), bob_tx.clone()];
+    txs.extend(invite_txs.iter().cloned());
+    txs.extend(accept_txs.iter().cloned());
+    kit.create_block(txs);
+
+    let transfer_tx = transfer(&alice, &bob, 40);
+    kit.create_block(vec![transfer_tx.clone()]);
+    kit.assert_tx_success(&transfer_tx);
+
+    // 3 signers require 2 approvals, the initiator one is not enough
+    assert!(kit.pool().is_empty());
+    assert_eq!(kit.wallet(&alice.0).pending_txs.len(), 1);
+
+    let sign_tx = sign(
+        SignTx {
+            origin: alice.0,
+            tx_hash: transfer_tx.hash(),
+            seed: 0,
+        },
+        &signers[1],
+    );
+    kit.create_block(vec![sign_tx.clone()]);
+    kit.assert_tx_success(&sign_tx);
+
+    let signed = kit.wallet(&alice.0);
+    assert_eq!(signed.pending_txs[0].approvals, vec![signers[1].0]);
+    assert_eq!(kit.pool().len(), 1);
+    let confirmation_tx = kit.pool()[0].clone();
+
+    kit.create_block(vec![]);
+    kit.assert_tx_success(&confirmation_tx);
+
+    let alice_wallet = kit.wallet(&alice.0);
+    assert_eq!(alice_wallet.balance, 60);
+    assert!(alice_wallet.pending_txs.is_empty());
+    assert_eq!(alice_wallet.txs.len(), 1);
+    assert_eq!(alice_wallet.txs[0].approvals, vec![signers[1].0]);
+    assert_eq!(alice_wallet.txs[0].confirmation_block, 3);
+
+    let mut history = vec![&alice_tx];
+    history.extend(invite_txs.iter());
+    history.extend(accept_txs.iter());
+    history.extend(&[&transfer_tx, &sign_tx, &confirmation_tx, &confirmation_tx]);
+    assert_eq!(alice_wallet.history_hash, history_hash(&history));
+
+    let bob_wallet = kit.wallet(&bob.0);
+    assert_eq!(bob_wallet.balance, 140);
+    assert_eq!(
+        bob_wallet.history_hash,
+        history_hash(&[&bob_tx, &confirmation_tx])
+    );
+}
+
+#[test]
+fn spending_limit_counts_pending_transfers() {
+    let mut kit = TestKit::with_config(|config| config.initial_balance = 100);
+    let alice = crypto::gen_keypair();
+    let bob = crypto::gen_keypair();
+    let signers: Vec<_> = (0..3).map(|_| crypto::gen_keypair()).collect();
+
+    let mut txs = vec![create_wallet(&alice, "Alice"), create_wallet(&bob, "Bob")];
+    for signer in &signers {
+        let (invite_tx, accept_tx) = add_signer(&alice, signer);
+        txs.push(invite_tx);
+        txs.push(accept_tx);
+    }
+    kit.create_block(txs);
+
+    let limit_tx = sign(
+        SetSpendingLimitTx {
+            amount: 50,
+            period: 10,
+            seed: 0,
+        },
+        &alice,
+    );
+    kit.create_block(vec![limit_tx.clone()]);
+    let sign_tx = sign(
+        SignTx {
+            origin: alice.0,
+            tx_hash: limit_tx.hash(),
+            seed: 0,
+        },
+        &signers[0],
+    );
+    kit.create_block(vec![sign_tx]);
+    kit.create_block(vec![]);
+    assert_eq!(kit.wallet(&alice.0).spending_limit.amount, 50);
+
+    // Second transfer exceeds the limit together with the pending first one
+    let first = transfer(&alice, &bob, 30);
+    let second = transfer(&alice, &bob, 25);
+    kit.create_block(vec![first.clone(), second.clone()]);
+    kit.assert_tx_success(&first);
+    kit.assert_tx_success(&second);
+    assert_eq!(kit.pool().len(), 1);
+
+    let alice_wallet = kit.wallet(&alice.0);
+    assert!(alice_wallet.spendings.is_empty());
+    assert!(alice_wallet.pending_txs[0].within_limit);
+    assert!(!alice_wallet.pending_txs[1].within_limit);
+
+    // Spending is recorded once the transfer is executed
+    let confirmation_tx = kit.pool()[0].clone();
+    kit.create_block(vec![]);
+    kit.assert_tx_success(&confirmation_tx);
+
+    let alice_wallet = kit.wallet(&alice.0);
+    assert_eq!(alice_wallet.balance, 70);
+    assert_eq!(
+        alice_wallet.spendings,
+        vec![Spending {
+            height: kit.height(),
+            amount: 30,
+        }]
+    );
+    assert_eq!(alice_wallet.pending_txs.len(), 1);
+    assert_eq!(alice_wallet.pending_txs[0].tx_hash, second.hash());
+}
+
+#[test]
+fn confirmation_rejects_transfer_exceeding_balance() {
+    let mut kit = TestKit::with_config(|config| config.initial_balance = 100);
+    let alice = crypto::gen_keypair();
+    let bob = crypto::gen_keypair();
+    let carol = crypto::gen_keypair();
+    let signers: Vec<_> = (0..3).map(|_| crypto::gen_keypair()).collect();
+
+    let mut txs = vec![
+        create_wallet(&alice, "Alice"),
+        create_wallet(&bob, "Bob"),
+        create_wallet(&carol, "Carol"),
+    ];
+    for signer in &signers {
+        let (invite_tx, accept_tx) = add_signer(&alice, signer);
+        txs.push(invite_tx);
+        txs.push(accept_tx);
+    }
+    kit.create_block(txs);
+
+    // Both transfers fit the balance while they are pending, but not together
+    let to_bob = transfer(&alice, &bob, 80);
+    let to_carol = transfer(&alice, &carol, 80);
+    kit.create_block(vec![to_bob.clone(), to_carol.clone()]);
+    kit.assert_tx_success(&to_bob);
+    kit.assert_tx_success(&to_carol);
+
+    let sign_txs: Vec<_> = [&to_bob, &to_carol]
+        .iter()
+        .map(|tx| {
+            sign(
+                SignTx {
+                    origin: alice.0,
+                    tx_hash: tx.hash(),
+                    seed: 0,
+                },
+                &signers[0],
+            )
+        })
+        .collect();
+    kit.create_block(sign_txs);
+    assert_eq!(kit.pool().len(), 2);
+
+    let confirmation_txs = kit.pool().to_vec();
+    kit.create_block(vec![]);
+    kit.assert_tx_success(&confirmation_txs[0]);
+    kit.assert_tx_success(&confirmation_txs[1]);
+
+    let alice_wallet = kit.wallet(&alice.0);
+    assert_eq!(alice_wallet.balance, 20);
+    assert!(alice_wallet.pending_txs.is_empty());
+    assert_eq!(alice_wallet.txs.len(), 1);
+    assert_eq!(
+        kit.wallet(&bob.0).balance + kit.wallet(&carol.0).balance,
+        280
+    );
+
+    // Rejected transfer isn't confirmed again
+    let rejected_hash = if alice_wallet.txs[0].tx_hash == to_bob.hash() {
+        to_carol.hash()
+    } else {
+        to_bob.hash()
+    };
+    let rejected = confirmation_txs
+        .iter()
+        .map(|tx| Api::get_receipt(&kit.api(), ReceiptQuery { tx_hash: tx.hash() }).unwrap())
+        .find(|receipt| !receipt.rejections.is_empty())
+        .unwrap();
+    assert_eq!(
+        rejected.rejections,
+        vec![Rejection {
+            wallet: alice.0,
+            tx_hash: rejected_hash,
+            code: TxError::InsufficientCurrencyAmount as u32,
+            description: "Insufficient currency amount".to_owned(),
+        }]
+    );
+    assert_eq!(rejected.awaiting_consumed.len(), 1);
+    kit.create_block(vec![]);
+    assert!(kit.pool().is_empty());
+}
+
+#[test]
+fn approvals_after_threshold_confirm_once() {
+    let mut kit = TestKit::with_config(|config| config.initial_balance = 100);
+    let alice = crypto::gen_keypair();
+    let bob = crypto::gen_keypair();
+    let signers: Vec<_> = (0..3).map(|_| crypto::gen_keypair()).collect();
+
+    let mut txs = vec![create_wallet(&alice, "Alice"), create_wallet(&bob, "Bob")];
+    for signer in &signers {
+        let (invite_tx, accept_tx) = add_signer(&alice, signer);
+        txs.push(invite_tx);
+        txs.push(accept_tx);
+    }
+    kit.create_block(txs);
+
+    let transfer_tx = transfer(&alice, &bob, 40);
+    kit.create_block(vec![transfer_tx.clone()]);
+
+    // First approval reaches the threshold, the others are still accepted
+    let sign_txs: Vec<_> = signers
+        .iter()
+        .map(|signer| {
+            sign(
+                SignTx {
+                    origin: alice.0,
+                    tx_hash: transfer_tx.hash(),
+                    seed: 0,
+                },
+                signer,
+            )
+        })
+        .collect();
+    kit.create_block(sign_txs.clone());
+    for tx in &sign_txs {
+        kit.assert_tx_success(tx);
+    }
+    assert_eq!(kit.wallet(&alice.0).pending_txs[0].approvals.len(), 3);
+    assert_eq!(kit.pool().len(), 1);
+
+    let confirmation_tx = kit.pool()[0].clone();
+    kit.create_block(vec![]);
+    kit.assert_tx_success(&confirmation_tx);
+    assert!(kit.pool().is_empty());
+    assert_eq!(kit.wallet(&alice.0).balance, 60);
+}
+
+#[test]
+fn confirmation_consumes_awaiting_entry() {
+    let mut kit = TestKit::with_config(|config| config.initial_balance = 100);
+    let alice = crypto::gen_keypair();
+    let bob = crypto::gen_keypair();
+    let signers: Vec<_> = (0..3).map(|_| crypto::gen_keypair()).collect();
+
+    let mut txs = vec![create_wallet(&alice, "Alice"), create_wallet(&bob, "Bob")];
+    for signer in &signers {
+        let (invite_tx, accept_tx) = add_signer(&alice, signer);
+        txs.push(invite_tx);
+        txs.push(accept_tx);
+    }
+    kit.create_block(txs);
+
+    let transfer_tx = transfer(&alice, &bob, 40);
+    let sign_tx = sign(
+        SignTx {
+            origin: alice.0,
+            tx_hash: transfer_tx.hash(),
+            seed: 0,
+        },
+        &signers[0],
+    );
+    kit.create_block(vec![transfer_tx.clone()]);
+    kit.create_block(vec![sign_tx.clone()]);
+    assert_eq!(kit.awaiting_txs(), vec![transfer_tx.hash()]);
+
+    let confirmation_tx = kit.pool()[0].clone();
+    kit.create_block(vec![]);
+    kit.assert_tx_success(&confirmation_tx);
+    assert!(kit.awaiting_txs().is_empty());
+
+    let receipt = |tx_hash| Api::get_receipt(&kit.api(), ReceiptQuery { tx_hash }).unwrap();
+    let sign_receipt = receipt(sign_tx.hash());
+    assert_eq!(sign_receipt.awaiting_created, vec![transfer_tx.hash()]);
+    let confirmation_receipt = receipt(confirmation_tx.hash());
+    assert_eq!(
+        confirmation_receipt.awaiting_consumed,
+        vec![transfer_tx.hash()]
+    );
+}
+
+#[test]
+fn receipts_without_effects() {
+    let mut kit = TestKit::new();
+    let alice = crypto::gen_keypair();
+    let signer = crypto::gen_keypair();
+
+    // Wallet starts with zero balance and the transactions are free
+    let (invite_tx, accept_tx) = add_signer(&alice, &signer);
+    let group_tx = set_signer_group(&alice, "finance", &[signer.clone()], 1);
+    let txs = vec![
+        create_wallet(&alice, "Alice"),
+        invite_tx,
+        accept_tx,
+        group_tx,
+    ];
+    kit.create_block(txs.clone());
+    for tx in &txs {
+        kit.assert_tx_success(tx);
+    }
+
+    let height = kit.height();
+    let receipts = Api::get_block_receipts(&kit.api(), BlockReceiptsQuery { height }).unwrap();
+    let expected: Vec<_> = txs
+        .iter()
+        .map(|tx| Receipt::new(&tx.hash(), height))
+        .collect();
+    assert_eq!(receipts, expected);
+}
+
+#[test]
+fn wallets_pages_with_zero_limit() {
+    let mut kit = TestKit::new();
+    let alice = crypto::gen_keypair();
+    let bob = crypto::gen_keypair();
+    kit.create_block(vec![
+        create_wallet(&alice, "Alice"),
+        create_wallet(&bob, "Bob"),
+    ]);
+
+    // Treasury wallet is listed too
+    let mut expected = vec![alice.0, bob.0, kit.issuer().0];
+    expected.sort();
+
+    let mut keys = vec![];
+    let mut from = None;
+    loop {
+        let query = WalletsQuery {
+            from,
+            limit: Some(0),
+            min_balance: None,
+            has_signers: None,
+            name_prefix: None,
+        };
+        let page = Api::get_wallets(&kit.api(), query).unwrap();
+        assert_eq!(page.wallets.len(), 1);
+        keys.extend(page.wallets.iter().map(|wallet| wallet.pub_key));
+
+        from = page.next;
+        if from.is_none() {
+            break;
+        }
+    }
+    assert_eq!(keys, expected);
+}
+
+#[test]
+fn service_config_in_state_hash() {
+    let mut kit = TestKit::new();
+    let validator = kit.validator();
+    let config_hash = |kit: &TestKit| {
+        BlockchainSchema::new(kit.blockchain().snapshot())
+            .state_hash_aggregator()
+            .get(&Blockchain::service_table_unique_key(SERVICE_ID, 0))
+            .unwrap()
+    };
+
+    let mut config = Schema::new(kit.blockchain().snapshot()).config();
+    assert_eq!(config_hash(&kit), config.hash());
+
+    // Single validator vote activates the proposal in the block it's committed in
+    config.fees.transfer = 1;
+    let propose_tx = sign(
+        ProposeServiceConfigTx {
+            config: config.clone(),
+            activation_height: kit.height() + 2,
+        },
+        &validator,
+    );
+    kit.create_block(vec![propose_tx.clone()]);
+    kit.assert_tx_success(&propose_tx);
+    assert_eq!(Schema::new(kit.blockchain().snapshot()).config(), config);
+    assert_eq!(config_hash(&kit), config.hash());
+}
+
+#[test]
+fn inviting_wallet_pays_acceptance_fee() {
+    let mut kit = TestKit::with_config(|config| {
+        config.initial_balance = 100;
+        config.fees.accept_signer_invite = 2;
+    });
+    let alice = crypto::gen_keypair();
+    let signer = crypto::gen_keypair();
+    let treasury = kit.issuer().0;
+
+    kit.create_block(vec![create_wallet(&alice, "Alice")]);
+    let treasury_balance = kit.wallet(&treasury).balance;
+
+    // Invited key has no wallet of its own
+    let (invite_tx, accept_tx) = add_signer(&alice, &signer);
+    kit.create_block(vec![invite_tx, accept_tx.clone()]);
+    kit.assert_tx_success(&accept_tx);
+
+    let alice_wallet = kit.wallet(&alice.0);
+    assert!(alice_wallet.is_signer(&signer.0));
+    assert_eq!(alice_wallet.balance, 98);
+    assert_eq!(kit.wallet(&treasury).balance, treasury_balance + 2);
+    assert!(kit.find_wallet(&signer.0).is_none());
+}
+
+#[test]
+fn weighted_threshold_transfer() {
+    let mut kit = TestKit::with_config(|config| config.initial_balance = 100);
+    let alice = crypto::gen_keypair();
+    let bob = crypto::gen_keypair();
+    let cfo = crypto::gen_keypair();
+    let leads: Vec<_> = (0..2).map(|_| crypto::gen_keypair()).collect();
+
+    let (cfo_invite, cfo_accept) = add_weighted_signer(&alice, &cfo, 3);
+    let mut txs = vec![
+        create_wallet(&alice, "Alice"),
+        create_wallet(&bob, "Bob"),
+        cfo_invite,
+        cfo_accept,
+    ];
+    for lead in &leads {
+        let (invite_tx, accept_tx) = add_signer(&alice, lead);
+        txs.push(invite_tx);
+        txs.push(accept_tx);
+    }
+    kit.create_block(txs);
+
+    let transfer_tx = transfer(&alice, &bob, 40);
+    kit.create_block(vec![transfer_tx.clone()]);
+    kit.assert_tx_success(&transfer_tx);
+
+    // Total weight 5 requires weight 3, the initiator and a lead approvals weigh 2
+    let pending_tx = kit.wallet(&alice.0).pending_txs[0].clone();
+    assert_eq!(pending_tx.required_weight, 3);
+    assert_eq!(pending_tx.approved_weight, 1);
+
+    let lead_sign = sign(
+        SignTx {
+            origin: alice.0,
+            tx_hash: transfer_tx.hash(),
+            seed: 0,
+        },
+        &leads[0],
+    );
+    kit.create_block(vec![lead_sign.clone()]);
+    kit.assert_tx_success(&lead_sign);
+    assert!(kit.pool().is_empty());
+    assert_eq!(kit.wallet(&alice.0).pending_txs[0].approved_weight, 2);
+
+    let cfo_sign = sign(
+        SignTx {
+            origin: alice.0,
+            tx_hash: transfer_tx.hash(),
+            seed: 0,
+        },
+        &cfo,
+    );
+    kit.create_block(vec![cfo_sign.clone()]);
+    kit.assert_tx_success(&cfo_sign);
+    assert_eq!(kit.wallet(&alice.0).pending_txs[0].approved_weight, 5);
+    assert_eq!(kit.pool().len(), 1);
+
+    let confirmation_tx = kit.pool()[0].clone();
+    kit.create_block(vec![]);
+    kit.assert_tx_success(&confirmation_tx);
+
+    let alice_wallet = kit.wallet(&alice.0);
+    assert_eq!(alice_wallet.balance, 60);
+    assert_eq!(alice_wallet.txs[0].approvals, vec![leads[0].0, cfo.0]);
+}
+
+#[test]
+fn signer_groups_quorums() {
+    let mut kit = TestKit::with_config(|config| config.initial_balance = 100);
+    let alice = crypto::gen_keypair();
+    let bob = crypto::gen_keypair();
+    let finance: Vec<_> = (0..2).map(|_| crypto::gen_keypair()).collect();
+    let engineering: Vec<_> = (0..3).map(|_| crypto::gen_keypair()).collect();
+
+    let mut txs = vec![create_wallet(&alice, "Alice"), create_wallet(&bob, "Bob")];
+    for signer in finance.iter().chain(&engineering) {
+        let (invite_tx, accept_tx) = add_signer(&alice, signer);
+        txs.push(invite_tx);
+        txs.push(accept_tx);
+    }
+    kit.create_block(txs);
+
+    let finance_tx = set_signer_group(&alice, "finance", &finance, 1);
+    let engineering_tx = set_signer_group(&alice, "engineering", &engineering, 2);
+    kit.create_block(vec![finance_tx.clone(), engineering_tx.clone()]);
+    kit.assert_tx_success(&finance_tx);
+    kit.assert_tx_success(&engineering_tx);
+    assert_eq!(kit.wallet(&alice.0).groups.len(), 2);
+
+    let transfer_tx = transfer(&alice, &bob, 40);
+    kit.create_block(vec![transfer_tx.clone()]);
+    kit.assert_tx_success(&transfer_tx);
+
+    // 5 signers require weight 3, which is reached without the second engineer
+    let sign_txs: Vec<_> = [&finance[0], &engineering[0]]
+        .iter()
+        .map(|signer| {
+            sign(
+                SignTx {
+                    origin: alice.0,
+                    tx_hash: transfer_tx.hash(),
+                    seed: 0,
+                },
+                signer,
+            )
+        })
+        .collect();
+    kit.create_block(sign_txs);
+    assert!(kit.pool().is_empty());
+
+    let pending_tx = kit.wallet(&alice.0).pending_txs[0].clone();
+    assert_eq!(pending_tx.approved_weight, pending_tx.required_weight);
+    let progress: Vec<_> = pending_tx
+        .groups
+        .iter()
+        .map(|group| (group.name.as_str(), group.approved_weight, group.threshold))
+        .collect();
+    assert_eq!(progress, vec![("finance", 1, 1), ("engineering", 1, 2)]);
+
+    let sign_tx = sign(
+        SignTx {
+            origin: alice.0,
+            tx_hash: transfer_tx.hash(),
+            seed: 0,
+        },
+        &engineering[1],
+    );
+    kit.create_block(vec![sign_tx.clone()]);
+    kit.assert_tx_success(&sign_tx);
+    assert_eq!(kit.pool().len(), 1);
+
+    let confirmation_tx = kit.pool()[0].clone();
+    kit.create_block(vec![]);
+    kit.assert_tx_success(&confirmation_tx);
+    assert_eq!(kit.wallet(&alice.0).balance, 60);
+}
+
+#[test]
+fn revoked_approval_clears_awaiting_confirmation() {
+    let mut kit = TestKit::with_config(|config| config.initial_balance = 100);
+    let alice = crypto::gen_keypair();
+    let bob = crypto::gen_keypair();
+    let signers: Vec<_> = (0..3).map(|_| crypto::gen_keypair()).collect();
+
+    let mut txs = vec![create_wallet(&alice, "Alice"), create_wallet(&bob, "Bob")];
+    for signer in &signers {
+        let (invite_tx, accept_tx) = add_signer(&alice, signer);
+        txs.push(invite_tx);
+        txs.push(accept_tx);
+    }
+    kit.create_block(txs);
+
+    let transfer_tx = transfer(&alice, &bob, 40);
+    kit.create_block(vec![transfer_tx.clone()]);
+
+    // Approval reaches the threshold of 3 signers, but is revoked in the same block
+    let sign_tx = sign(
+        SignTx {
+            origin: alice.0,
+            tx_hash: transfer_tx.hash(),
+            seed: 0,
+        },
+        &signers[0],
+    );
+    let revoke_tx = sign(
+        RevokeApprovalTx {
+            origin: alice.0,
+            tx_hash: transfer_tx.hash(),
+        },
+        &signers[0],
+    );
+    kit.create_block(vec![sign_tx.clone(), revoke_tx.clone()]);
+    kit.assert_tx_success(&sign_tx);
+    kit.assert_tx_success(&revoke_tx);
+    assert!(kit.pool().is_empty());
+    assert!(kit.awaiting_txs().is_empty());
+    let receipt = Api::get_receipt(
+        &kit.api(),
+        ReceiptQuery {
+            tx_hash: revoke_tx.hash(),
+        },
+    );
+    assert_eq!(receipt.unwrap().awaiting_consumed, vec![transfer_tx.hash()]);
+
+    let pending_tx = kit.wallet(&alice.0).pending_txs[0].clone();
+    assert!(pending_tx.approvals.is_empty());
+    assert_eq!(pending_tx.approved_weight, 1);
+
+    // Revoked approval is given again with another seed
+    let sign_tx = sign(
+        SignTx {
+            origin: alice.0,
+            tx_hash: transfer_tx.hash(),
+            seed: 1,
+        },
+        &signers[0],
+    );
+    kit.create_block(vec![sign_tx.clone()]);
+    kit.assert_tx_success(&sign_tx);
+    assert_eq!(kit.pool().len(), 1);
+
+    let confirmation_tx = kit.pool()[0].clone();
+    kit.create_block(vec![]);
+    kit.assert_tx_success(&confirmation_tx);
+    assert_eq!(kit.wallet(&alice.0).txs[0].approvals, vec![signers[0].0]);
+}
+
+#[test]
+fn after_commit_broadcasts_confirmation() {
+    let mut kit = TestKit::with_config(|config| config.initial_balance = 100);
+    let alice = crypto::gen_keypair();
+    let bob = crypto::gen_keypair();
+
+    kit.create_block(vec![
+        create_wallet(&alice, "Alice"),
+        create_wallet(&bob, "Bob"),
+    ]);
+    assert!(kit.pool().is_empty());
+
+    let transfer_tx = transfer(&alice, &bob, 10);
+    kit.create_block(vec![transfer_tx.clone()]);
+
+    assert_eq!(kit.pool().len(), 1);
+    let confirmation_tx = &kit.pool()[0];
+    assert_eq!(confirmation_tx.author(), kit.validator().0);
+
+    let confirmation = confirmation(confirmation_tx);
+    assert_eq!(confirmation.tx_hash, transfer_tx.hash());
+    assert_eq!(confirmation.sender, alice.0);
+    assert_eq!(confirmation.confirmation_block, kit.height());
+}
+
+#[test]
+fn simulation_shows_fee_payment() {
+    let mut kit = TestKit::with_config(|config| {
+        config.initial_balance = 100;
+        config.fees.sign = 3;
+    });
+    let alice = crypto::gen_keypair();
+    let bob = crypto::gen_keypair();
+    let carol = crypto::gen_keypair();
+    let treasury = kit.issuer().0;
+
+    kit.create_block(vec![
+        create_wallet(&alice, "Alice"),
+        create_wallet(&bob, "Bob"),
+        create_wallet(&carol, "Carol"),
+    ]);
+    let (add_tx, accept_tx) = add_signer(&alice, &bob);
+    kit.create_block(vec![add_tx, accept_tx]);
+
+    let transfer_tx = transfer(&alice, &carol, 10);
+    kit.create_block(vec![transfer_tx.clone()]);
+
+    let sign_tx = sign(
+        SignTx {
+            origin: alice.0,
+            tx_hash: transfer_tx.hash(),
+            seed: 0,
+        },
+        &bob,
+    );
+    let query = TransactionHex {
+        tx_body: hex::encode(sign_tx.serialize()),
+    };
+    let result = Api::simulate_transaction(&kit.api(), query).unwrap();
+    assert_eq!(result.status, SimulationStatus::Success);
+
+    let balance_change = |pub_key| {
+        let diff = result
+            .diffs
+            .iter()
+            .find(|diff| diff.pub_key == pub_key)
+            .unwrap_or_else(|| panic!("No diff for {:?}", pub_key));
+        let before = diff.before.as_ref().unwrap().balance as i64;
+        let after = diff.after.as_ref().unwrap().balance as i64;
+        after - before
+    };
+    // Bob pays the fee for his approval, Alice's wallet gets the approval
+    assert_eq!(balance_change(bob.0), -3);
+    assert_eq!(balance_change(treasury), 3);
+    assert!(result.diffs.iter().any(|diff| diff.pub_key == alice.0));
+}
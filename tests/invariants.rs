@@ -0,0 +1,388 @@
+mod common;
+
+use common::{sign, KeyPair, TestKit};
+use exonum::crypto::{self, Hash, PublicKey, Seed, SEED_LENGTH};
+use exonum::messages::{RawTransaction, Signed};
+use exonum_multisig::api::Api;
+use exonum_multisig::asset::NATIVE_ASSET_ID;
+use exonum_multisig::schema::Schema;
+use exonum_multisig::transaction::{
+    AcceptSignerInviteTx, AddSignerTx, BatchTransferTx, BurnTx, CreateWalletTx, GroupQuorum,
+    MintTx, Payment, PendingKind, RevokeApprovalTx, SetSignerGroupTx, SetSpendingLimitTx, SignTx,
+    TransferTx,
+};
+use exonum_multisig::wallet::{find_signer, total_weight, Signer, Wallet};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+const USERS: usize = 4;
+/// Users with wallets created before the random blocks, the rest may create them later.
+const FUNDED: usize = 3;
+const MAX_AMOUNT: u64 = 150;
+
+/// Wallet transaction with wallets referred to by user index.
+#[derive(Clone, Debug)]
+enum Action {
+    CreateWallet(usize),
+    /// Wallet owner, invited signer and its weight.
+    AddSigner(usize, usize, u64),
+    /// Invited signer and the wallet owner.
+    AcceptInvite(usize, usize),
+    Transfer(usize, usize, u64),
+    /// Signer, origin wallet and index of its pending transaction.
+    Sign(usize, usize, usize),
+    /// Signer, origin wallet and index of its pending transaction.
+    Revoke(usize, usize, usize),
+    Mint(u64),
+    Burn(u64),
+    BatchTransfer(usize, Vec<(usize, u64)>),
+    SetSpendingLimit(usize, u64, u64),
+    /// Wallet owner, group name index, members and threshold.
+    SetSignerGroup(usize, usize, Vec<usize>, u64),
+}
+
+fn user() -> impl Strategy<Value = usize> {
+    0..USERS
+}
+
+fn amount() -> impl Strategy<Value = u64> {
+    0..MAX_AMOUNT
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        2 => user().prop_map(Action::CreateWallet),
+        3 => (user(), user(), 1..4u64)
+            .prop_map(|(user, signer, weight)| Action::AddSigner(user, signer, weight)),
+        3 => (user(), user()).prop_map(|(signer, user)| Action::AcceptInvite(signer, user)),
+        4 => (user(), user(), amount())
+            .prop_map(|(from, to, amount)| Action::Transfer(from, to, amount)),
+        4 => (user(), user(), 0..4usize)
+            .prop_map(|(signer, origin, index)| Action::Sign(signer, origin, index)),
+        2 => (user(), user(), 0..4usize)
+            .prop_map(|(signer, origin, index)| Action::Revoke(signer, origin, index)),
+        1 => amount().prop_map(Action::Mint),
+        1 => amount().prop_map(Action::Burn),
+        1 => (user(), vec((user(), amount()), 0..3))
+            .prop_map(|(from, payments)| Action::BatchTransfer(from, payments)),
+        1 => (user(), amount(), 0..4u64)
+            .prop_map(|(user, amount, period)| Action::SetSpendingLimit(user, amount, period)),
+        2 => (user(), 0..2usize, vec(user(), 0..3), 0..4u64)
+            .prop_map(|(user, name, members, threshold)| {
+                Action::SetSignerGroup(user, name, members, threshold)
+            }),
+    ]
+}
+
+/// State the invariants are checked against, collected between blocks.
+struct Model {
+    kit: TestKit,
+    users: Vec<KeyPair>,
+    /// Hashes of the submitted transactions, the same transaction is submitted once.
+    submitted: HashSet<Hash>,
+    seed: u64,
+    /// Approval weight required by the pending transaction, its signers and groups, frozen
+    /// when it was created, by its hash.
+    required: HashMap<Hash, (u64, Vec<Signer>, Vec<GroupQuorum>)>,
+    /// Wallets which have set a spending limit, their transfers may skip approvals.
+    limited: HashSet<PublicKey>,
+}
+
+impl Model {
+    fn new() -> Self {
+        let kit = TestKit::with_config(|config| {
+            config.initial_balance = 100;
+            // All signers approve, so that a few signers make a threshold worth checking
+            config.approval_numerator = 1;
+            config.approval_denominator = 1;
+            config.fees.transfer = 1;
+            config.fees.sign = 1;
+            config.fees.batch_transfer = 2;
+        });
+        let users = (0..USERS)
+            .map(|index| crypto::gen_keypair_from_seed(&Seed::new([index as u8; SEED_LENGTH])))
+            .collect();
+
+        Self {
+            kit,
+            users,
+            submitted: HashSet::new(),
+            seed: 0,
+            required: HashMap::new(),
+            limited: HashSet::new(),
+        }
+    }
+
+    fn wallets(&self) -> BTreeMap<PublicKey, Wallet> {
+        Schema::new(self.kit.blockchain().snapshot())
+            .wallets()
+            .iter()
+            .collect()
+    }
+
+    /// Returns hash of the wallet pending transaction, wrapping the index around.
+    fn pending_tx_hash(&self, pub_key: &PublicKey, index: usize) -> Hash {
+        self.kit
+            .find_wallet(pub_key)
+            .and_then(|wallet| {
+                let len = wallet.pending_txs.len();
+                wallet
+                    .pending_txs
+                    .get(index % len.max(1))
+                    .map(|tx| tx.tx_hash)
+            })
+            .unwrap_or_else(Hash::zero)
+    }
+
+    fn next_seed(&mut self) -> u64 {
+        self.seed += 1;
+        self.seed
+    }
+
+    fn transaction(&mut self, action: &Action) -> Signed<RawTransaction> {
+        let issuer = self.kit.issuer();
+        let users = self.users.clone();
+        let seed = self.next_seed();
+
+        match *action {
+            Action::CreateWallet(user) => sign(
+                CreateWalletTx {
+                    name: format!("User {}", user),
+                },
+                &users[user],
+            ),
+            Action::AddSigner(user, signer, weight) => sign(
+                AddSignerTx {
+                    signer: users[signer].0,
+                    weight,
+                },
+                &users[user],
+            ),
+            Action::AcceptInvite(signer, user) => sign(
+                AcceptSignerInviteTx {
+                    wallet: users[user].0,
+                },
+                &users[signer],
+            ),
+            Action::Transfer(from, to, amount) => sign(
+                TransferTx {
+                    recipient: users[to].0,
+                    asset_id: NATIVE_ASSET_ID.to_owned(),
+                    amount,
+                    seed,
+                },
+                &users[from],
+            ),
+            Action::Sign(signer, origin, index) => sign(
+                SignTx {
+                    origin: users[origin].0,
+                    tx_hash: self.pending_tx_hash(&users[origin].0, index),
+                    seed,
+                },
+                &users[signer],
+            ),
+            Action::Revoke(signer, origin, index) => sign(
+                RevokeApprovalTx {
+                    origin: users[origin].0,
+                    tx_hash: self.pending_tx_hash(&users[origin].0, index),
+                },
+                &users[signer],
+            ),
+            Action::Mint(amount) => sign(MintTx { amount, seed }, &issuer),
+            Action::Burn(amount) => sign(BurnTx { amount, seed }, &issuer),
+            Action::BatchTransfer(from, ref payments) => sign(
+                BatchTransferTx {
+                    payments: payments
+                        .iter()
+                        .map(|&(to, amount)| Payment {
+                            recipient: users[to].0,
+                            amount,
+                        })
+                        .collect(),
+                    asset_id: NATIVE_ASSET_ID.to_owned(),
+                    seed,
+                },
+                &users[from],
+            ),
+            Action::SetSpendingLimit(user, amount, period) => sign(
+                SetSpendingLimitTx {
+                    amount,
+                    period,
+                    seed,
+                },
+                &users[user],
+            ),
+            Action::SetSignerGroup(user, name, ref members, threshold) => {
+                let mut members: Vec<_> = members.iter().map(|&member| users[member].0).collect();
+                members.sort();
+                members.dedup();
+                sign(
+                    SetSignerGroupTx {
+                        name: format!("Group {}", name),
+                        members,
+                        threshold,
+                    },
+                    &users[user],
+                )
+            }
+        }
+    }
+
+    fn apply_block(&mut self, actions: &[Action]) -> Result<(), TestCaseError> {
+        let mut txs = vec![];
+        for action in actions {
+            let tx = self.transaction(action);
+            if self.submitted.insert(tx.hash()) {
+                txs.push(tx);
+            }
+        }
+
+        let before = self.wallets();
+        self.kit.create_block(txs);
+        self.check(&before)
+    }
+
+    fn check(&mut self, before: &BTreeMap<PublicKey, Wallet>) -> Result<(), TestCaseError> {
+        let snapshot = self.kit.blockchain().snapshot();
+        let schema = Schema::new(&snapshot);
+        let config = schema.config();
+        let wallets = self.wallets();
+
+        let supply = Api::get_supply(&self.kit.api(), ()).unwrap().total_supply;
+        let balances: u64 = wallets.values().map(|wallet| wallet.balance).sum();
+        prop_assert_eq!(
+            supply,
+            balances,
+            "Total supply differs from the wallet balances"
+        );
+
+        for (pub_key, wallet) in &wallets {
+            let history_len = schema.wallet_history(pub_key).len();
+            prop_assert_eq!(
+                wallet.history_len,
+                history_len,
+                "History length of the wallet {:?} differs from its history",
+                pub_key
+            );
+
+            prop_assert!(
+                wallet
+                    .invites
+                    .iter()
+                    .all(|invite| !wallet.is_signer(&invite.pub_key)),
+                "Invited key of the wallet {:?} is already its signer",
+                pub_key
+            );
+
+            if wallet.spending_limit.amount > 0 {
+                self.limited.insert(*pub_key);
+            }
+
+            // Signers added in the block after the transaction aren't taken into account,
+            // so the model required weight is the lower bound of the frozen one
+            let signers_weight = before
+                .get(pub_key)
+                .map_or(0, |wallet| total_weight(&wallet.signers));
+            for tx in &wallet.pending_txs {
+                let approved_weight = approved_weight(&tx.signers, &tx.approvals);
+                prop_assert!(
+                    approved_weight.is_some(),
+                    "Transaction {:?} is approved by a key out of its signers",
+                    tx.tx_hash
+                );
+                prop_assert_eq!(
+                    approved_weight,
+                    Some(tx.approved_weight),
+                    "Approved weight of the transaction {:?} differs from its approvals",
+                    tx.tx_hash
+                );
+                for group in &tx.groups {
+                    prop_assert_eq!(
+                        group_weight(&tx.signers, group, &tx.approvals),
+                        group.approved_weight,
+                        "Group {} weight of the transaction {:?} differs from its approvals",
+                        group.name,
+                        tx.tx_hash
+                    );
+                }
+                if self.required.contains_key(&tx.tx_hash) {
+                    continue;
+                }
+
+                // Transfers within the spending limit don't need approvals
+                let limited = tx.kind == PendingKind::Transfer && self.limited.contains(pub_key);
+                let expected = config.required_weight(signers_weight);
+                prop_assert!(
+                    limited || tx.required_weight >= expected,
+                    "Transaction {:?} requires weight {}, at least {} expected",
+                    tx.tx_hash,
+                    tx.required_weight,
+                    expected
+                );
+                self.required.insert(
+                    tx.tx_hash,
+                    (tx.required_weight, tx.signers.clone(), tx.groups.clone()),
+                );
+            }
+
+            for tx in &wallet.txs {
+                let (required, ref signers, ref groups) = self.required[&tx.tx_hash];
+                let approved_weight = approved_weight(signers, &tx.approvals).unwrap_or(0);
+                prop_assert!(
+                    approved_weight >= required,
+                    "Transaction {:?} is confirmed with weight {}, {} required",
+                    tx.tx_hash,
+                    approved_weight,
+                    required
+                );
+                for group in groups {
+                    let group_weight = group_weight(signers, group, &tx.approvals);
+                    prop_assert!(
+                        group_weight >= group.threshold,
+                        "Transaction {:?} is confirmed with weight {} of the group {}, {} required",
+                        tx.tx_hash,
+                        group_weight,
+                        group.name,
+                        group.threshold
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the approval weight of the transaction, counting the initiator with weight 1,
+/// or `None` if one of the approvals is out of its signers.
+fn approved_weight(signers: &[Signer], approvals: &[PublicKey]) -> Option<u64> {
+    approvals.iter().try_fold(1, |total, key| {
+        find_signer(signers, key).map(|signer| total + signer.weight)
+    })
+}
+
+/// Returns the weight of the transaction approvals given by the group members.
+fn group_weight(signers: &[Signer], group: &GroupQuorum, approvals: &[PublicKey]) -> u64 {
+    approvals
+        .iter()
+        .filter(|key| group.members.contains(key))
+        .filter_map(|key| find_signer(signers, key))
+        .map(|signer| signer.weight)
+        .sum()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn invariants_hold_after_every_block(blocks in vec(vec(action(), 1..8), 1..10)) {
+        let mut model = Model::new();
+        let create_wallets: Vec<_> = (0..FUNDED).map(Action::CreateWallet).collect();
+        model.apply_block(&create_wallets)?;
+        for actions in &blocks {
+            model.apply_block(actions)?;
+        }
+        // Confirmations broadcasted after the last block
+        model.apply_block(&[])?;
+    }
+}